@@ -0,0 +1,21 @@
+// An extension Wired Tiger can load with `Connection::load_extension`,
+// registering the collator `numeric` on the loading connection.
+
+#[macro_use]
+extern crate wiredtiger;
+
+use wiredtiger::wiredtiger::Connection;
+use wiredtiger::extension::ExtensionConfig;
+use wiredtiger::collator::NumericCollator;
+
+fn init(connection: &mut Connection, _config: &ExtensionConfig)
+  -> Result<(), String> {
+  connection.add_collator("numeric", NumericCollator)
+}
+
+fn terminate(connection: &mut Connection) -> Result<(), String> {
+  let api = try!(connection.extension_api());
+  api.message("numeric extension unloaded")
+}
+
+wiredtiger_extension!(init, terminate);
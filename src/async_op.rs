@@ -0,0 +1,309 @@
+extern crate libc;
+
+use wiredtiger::{Connection, WT_NOTFOUND, c_str, get_error, string_from_ptr};
+use wiredtiger_def::{WT_ASYNC_CALLBACK, WT_ASYNC_OP, WT_AOP_SEARCH};
+use event_handler::guard;
+
+use self::libc::{c_int, c_char};
+use std::ffi::CString;
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// How an asynchronous operation completed.
+enum Completion {
+  Done,
+  Found(String),
+  NotFound
+}
+
+struct Shared {
+  completion: Option<Result<Completion, String>>,
+  waker: Option<Waker>
+}
+
+type OpMethod = Option<extern "C" fn(*mut WT_ASYNC_OP) -> c_int>;
+
+/// An operation queued to Wired Tiger's asynchronous worker threads, which
+/// resolves to its result once a worker has run it.
+///
+/// The operation runs whether or not the future is polled, and dropping it
+/// does not cancel the operation.
+pub struct AsyncOperation<T> {
+  shared: Arc<Mutex<Shared>>,
+  map: fn(Completion) -> Result<T, String>
+}
+
+impl<T> Future for AsyncOperation<T> {
+  type Output = Result<T, String>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<T, String>> {
+    let mut shared = self.shared.lock().unwrap();
+    match shared.completion.take() {
+      Some(Ok(completion)) => Poll::Ready((self.map)(completion)),
+      Some(Err(message)) => Poll::Ready(Err(message)),
+      None => {
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+      }
+    }
+  }
+}
+
+/// Exposes an operation's shared state as a `WT_ASYNC_CALLBACK`, laid out
+/// like `EventBridge`, keeping its key and value alive until Wired Tiger
+/// reports the operation as done.
+#[repr(C)]
+struct CallbackBridge {
+  wt_callback: WT_ASYNC_CALLBACK,
+  shared: Arc<Mutex<Shared>>,
+  key: Option<CString>,
+  value: Option<CString>
+}
+
+impl Connection {
+  /// Queues the insertion of `value` under `key` in the table `table`. The
+  /// table must have string keys and values, and the connection must be
+  /// opened with `ConnectionOptions::async_ops`.
+  /// # Examples
+  /// ```
+  /// let inserted = connection.async_insert("users", "alice", "admin");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the operation could not be
+  /// queued, and the future resolves to `Err(message)` if it failed.
+  pub fn async_insert(&self, table: &str, key: &str, value: &str)
+    -> Result<AsyncOperation<()>, String> {
+    self.queue(table, |op| op.insert, Some(key), Some(value), done)
+  }
+
+  /// Queues the replacement of the value of the existing `key` in the table
+  /// `table` with `value`.
+  /// # Failures
+  /// The function returns `Err(message)` if the operation could not be
+  /// queued, and the future resolves to `Err(message)` if it failed.
+  pub fn async_update(&self, table: &str, key: &str, value: &str)
+    -> Result<AsyncOperation<()>, String> {
+    self.queue(table, |op| op.update, Some(key), Some(value), done)
+  }
+
+  /// Queues the removal of `key` from the table `table`. The future
+  /// resolves to `false` if the key did not exist.
+  /// # Failures
+  /// The function returns `Err(message)` if the operation could not be
+  /// queued, and the future resolves to `Err(message)` if it failed.
+  pub fn async_remove(&self, table: &str, key: &str)
+    -> Result<AsyncOperation<bool>, String> {
+    self.queue(table, |op| op.remove, Some(key), None, found)
+  }
+
+  /// Queues the search of `key` in the table `table`. The future resolves to
+  /// the value of the key, or `None` if it does not exist.
+  /// # Examples
+  /// ```
+  /// let role = connection.async_search("users", "alice");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the operation could not be
+  /// queued, and the future resolves to `Err(message)` if it failed.
+  pub fn async_search(&self, table: &str, key: &str)
+    -> Result<AsyncOperation<Option<String>>, String> {
+    self.queue(table, |op| op.search, Some(key), None, value)
+  }
+
+  /// Queues the compaction of the table `table`.
+  /// # Failures
+  /// The function returns `Err(message)` if the operation could not be
+  /// queued, and the future resolves to `Err(message)` if it failed.
+  pub fn async_compact(&self, table: &str)
+    -> Result<AsyncOperation<()>, String> {
+    self.queue(table, |op| op.compact, None, None, done)
+  }
+
+  /// Waits until every queued asynchronous operation has completed.
+  /// # Failures
+  /// The function returns `Err(message)` if waiting failed.
+  pub fn async_flush(&self) -> Result<(), String> {
+    unsafe {
+      match (*self.wt_con).async_flush {
+        Some(async_flush) => {
+          let ret = async_flush(self.wt_con);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get async_flush".to_string())
+      }
+    }
+  }
+
+  /// Allocates an asynchronous operation on `table`, sets its key and value
+  /// and queues it with the method `method` returns. An operation allocated
+  /// but not queued is released with `release`.
+  fn queue<T>(&self, table: &str, method: fn(&WT_ASYNC_OP) -> OpMethod,
+    key: Option<&str>, value: Option<&str>,
+    map: fn(Completion) -> Result<T, String>)
+    -> Result<AsyncOperation<T>, String> {
+    unsafe {
+      let async_new_op = match (*self.wt_con).async_new_op {
+        Some(async_new_op) => async_new_op,
+        None => return Err("Failed to get async_new_op".to_string())
+      };
+
+      let shared = Arc::new(Mutex::new(Shared{
+        completion: None,
+        waker: None
+      }));
+      let bridge = Box::into_raw(Box::new(CallbackBridge{
+        wt_callback: WT_ASYNC_CALLBACK{ notify: Some(notify) },
+        shared: shared.clone(),
+        key: try!(key.map(c_str).transpose()),
+        value: try!(value.map(c_str).transpose())
+      }));
+
+      let uri = try!(c_str(&format!("table:{0}", table)));
+      let mut op: *mut WT_ASYNC_OP = ptr::null_mut();
+      let ret = async_new_op(self.wt_con,
+        uri.as_ptr(),
+        ptr::null(),
+        &mut (*bridge).wt_callback,
+        &mut op);
+
+      if ret != 0 {
+        drop(Box::from_raw(bridge));
+        return Err(get_error(ret));
+      }
+
+      if let Some(ref key) = (*bridge).key {
+        match (*op).set_key {
+          Some(set_key) => set_key(op, key.as_ptr()),
+          None => {
+            release(op, bridge);
+            return Err("Failed to get set_key".to_string());
+          }
+        }
+      }
+      if let Some(ref value) = (*bridge).value {
+        match (*op).set_value {
+          Some(set_value) => set_value(op, value.as_ptr()),
+          None => {
+            release(op, bridge);
+            return Err("Failed to get set_value".to_string());
+          }
+        }
+      }
+
+      let run = match method(&*op) {
+        Some(run) => run,
+        None => {
+          release(op, bridge);
+          return Err("Failed to get the async operation".to_string());
+        }
+      };
+      // Once queued the operation may complete, and free the bridge, on a
+      // worker thread before `run` returns.
+      let ret = run(op);
+
+      // Wired Tiger returns the operation to its pool when it fails to queue
+      // it, without calling `notify`.
+      if ret != 0 {
+        drop(Box::from_raw(bridge));
+        return Err(get_error(ret));
+      }
+
+      Ok(AsyncOperation{
+        shared: shared,
+        map: map
+      })
+    }
+  }
+}
+
+/// Returns the operation `op`, allocated but not queued, to Wired Tiger's
+/// pool. Wired Tiger 2.6 has no call freeing an operation, and only returns
+/// it to the pool once it has run, so `op` is queued as a search of the empty
+/// key whose result `notify` discards when it frees `bridge`. Without
+/// `set_key` or `search` the operation stays allocated until the connection
+/// is closed.
+unsafe fn release(op: *mut WT_ASYNC_OP, bridge: *mut CallbackBridge) {
+  if let (Some(set_key), Some(search)) = ((*op).set_key, (*op).search) {
+    (*bridge).value = None;
+    let key = CString::default();
+    set_key(op, key.as_ptr());
+    (*bridge).key = Some(key);
+
+    if search(op) == 0 {
+      return;
+    }
+  }
+  drop(Box::from_raw(bridge));
+}
+
+fn done(completion: Completion) -> Result<(), String> {
+  match completion {
+    Completion::NotFound => Err(unsafe { get_error(WT_NOTFOUND) }),
+    _ => Ok(())
+  }
+}
+
+fn found(completion: Completion) -> Result<bool, String> {
+  match completion {
+    Completion::NotFound => Ok(false),
+    _ => Ok(true)
+  }
+}
+
+fn value(completion: Completion) -> Result<Option<String>, String> {
+  match completion {
+    Completion::Found(value) => Ok(Some(value)),
+    _ => Ok(None)
+  }
+}
+
+/// Returns the value a search found, which is only valid until `notify`
+/// returns.
+unsafe fn search_value(op: *mut WT_ASYNC_OP) -> Result<String, String> {
+  match (*op).get_value {
+    Some(get_value) => {
+      let mut value: *const c_char = ptr::null();
+      let ret = get_value(op, &mut value);
+
+      if ret != 0 {
+        return Err(get_error(ret));
+      }
+
+      Ok(string_from_ptr(value))
+    }
+    None => Err("Failed to get get_value".to_string())
+  }
+}
+
+extern "C" fn notify(cb: *mut WT_ASYNC_CALLBACK, op: *mut WT_ASYNC_OP,
+  op_ret: c_int, _flags: u32) -> c_int {
+  guard(|| unsafe {
+    let bridge = Box::from_raw(cb as *mut CallbackBridge);
+    let is_search = match (*op).get_type {
+      Some(get_type) => get_type(op) == WT_AOP_SEARCH,
+      None => false
+    };
+
+    let completion = match op_ret {
+      0 if is_search => search_value(op).map(Completion::Found),
+      0 => Ok(Completion::Done),
+      WT_NOTFOUND => Ok(Completion::NotFound),
+      ret => Err(get_error(ret))
+    };
+
+    let mut shared = bridge.shared.lock().unwrap();
+    shared.completion = Some(completion);
+    if let Some(waker) = shared.waker.take() {
+      waker.wake();
+    }
+    0
+  })
+}
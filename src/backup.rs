@@ -0,0 +1,171 @@
+use wiredtiger;
+use wiredtiger::{Connection, ConnectionOptions, CursorOptions};
+
+use std::fs;
+use std::path::Path;
+
+/// A file copied as part of a backup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupFile {
+  pub name: String,
+  pub size: u64
+}
+
+impl Connection {
+  /// Copies the database files to the `destination` directory while the
+  /// database remains online and returns the list of copied files.
+  /// The directory is created if it does not exist.
+  /// # Examples
+  /// ```
+  /// let manifest = connection.backup_to("/var/backups/users");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if:
+  /// * The `backup:` cursor could not be opened
+  /// * The `destination` directory could not be created
+  /// * Any of the files failed to be copied
+  pub fn backup_to(&mut self, destination: &str)
+    -> Result<Vec<BackupFile>, String> {
+    let home = try!(self.home());
+    try!(fs::create_dir_all(destination).map_err(|e|
+      format!("Failed to create {0}: {1}", destination, e)));
+
+    let mut session = try!(self.open_session());
+    let mut cursor = try!(session.open_uri_cursor("backup:",
+      &CursorOptions::new()));
+    let mut files = Vec::new();
+
+    while let Some(name) = try!(cursor.next_key()) {
+      let size = try!(copy_file(&home, destination, &name));
+      files.push(BackupFile{
+        name: name,
+        size: size
+      });
+    }
+
+    // Closing the cursor releases the backup so files can be removed again.
+    drop(cursor);
+    Ok(files)
+  }
+
+  /// Copies the log files written since the last backup to the
+  /// `destination` directory and returns the list of copied files.
+  /// When `archive_logs` is `true` the copied log files are removed from the
+  /// database once the copy completes, so the next incremental backup only
+  /// contains newer records.
+  /// # Examples
+  /// ```
+  /// let manifest = connection.incremental_backup_to("/var/backups/users-1", true);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if:
+  /// * The `backup:` cursor could not be opened
+  /// * The `destination` directory could not be created
+  /// * Any of the files failed to be copied
+  /// * The log files could not be archived
+  pub fn incremental_backup_to(&mut self, destination: &str,
+    archive_logs: bool) -> Result<Vec<BackupFile>, String> {
+    let home = try!(self.home());
+    try!(fs::create_dir_all(destination).map_err(|e|
+      format!("Failed to create {0}: {1}", destination, e)));
+
+    let mut session = try!(self.open_session());
+    let mut cursor = try!(session.open_uri_cursor("backup:",
+      &CursorOptions::new().target(&["log:"])));
+    let mut files = Vec::new();
+
+    while let Some(name) = try!(cursor.next_key()) {
+      let size = try!(copy_file(&home, destination, &name));
+      files.push(BackupFile{
+        name: name,
+        size: size
+      });
+    }
+
+    if archive_logs {
+      try!(session.archive_logs(&mut cursor));
+    }
+
+    drop(cursor);
+    Ok(files)
+  }
+}
+
+/// Restores a database into the `home` directory from the `full` backup
+/// followed by the `incrementals` backups, in the order they were taken, and
+/// opens it with logging enabled so the changes in the incremental log files
+/// are replayed.
+/// # Examples
+/// ```
+/// let connection = backup::restore("/var/backups/users",
+///   &["/var/backups/users-1", "/var/backups/users-2"],
+///   "/var/lib/users");
+/// ```
+/// # Failures
+/// The function returns `Err(message)` if:
+/// * The `home` directory is not empty
+/// * Any of the backup files failed to be copied
+/// * The restored database failed to open
+pub fn restore(full: &str, incrementals: &[&str], home: &str)
+  -> Result<Connection, String> {
+  restore_with_options(full, incrementals,
+    &ConnectionOptions::new().home(home))
+}
+
+/// Restores a database into the home directory of `options` like `restore`
+/// and opens it configured with `options`, such as the extensions the
+/// database needs. Logging is enabled if `options` does not configure it.
+/// # Examples
+/// ```
+/// let options = ConnectionOptions::new()
+///   .home("/var/lib/users")
+///   .extension("/usr/local/lib/libwiredtiger_lz4.so");
+/// let connection = backup::restore_with_options("/var/backups/users",
+///   &["/var/backups/users-1"], &options);
+/// ```
+/// # Failures
+/// The function returns `Err(message)` if:
+/// * `options` has no home directory
+/// * The home directory is not empty
+/// * Any of the backup files failed to be copied
+/// * The restored database failed to open
+pub fn restore_with_options(full: &str, incrementals: &[&str],
+  options: &ConnectionOptions) -> Result<Connection, String> {
+  let home = match options.home_dir() {
+    Some(home) => home,
+    None => return Err("The home directory is not set".to_string())
+  };
+  try!(fs::create_dir_all(home).map_err(|e|
+    format!("Failed to create {0}: {1}", home, e)));
+  let mut existing = try!(fs::read_dir(home).map_err(|e|
+    format!("Failed to read {0}: {1}", home, e)));
+  if existing.next().is_some() {
+    return Err(format!("{0} is not empty", home));
+  }
+
+  try!(copy_dir(full, home));
+  for incremental in incrementals {
+    try!(copy_dir(incremental, home));
+  }
+
+  wiredtiger::open_with_options(&options.with_log())
+}
+
+fn copy_dir(from: &str, to: &str) -> Result<(), String> {
+  let entries = try!(fs::read_dir(from).map_err(|e|
+    format!("Failed to read {0}: {1}", from, e)));
+
+  for entry in entries {
+    let entry = try!(entry.map_err(|e|
+      format!("Failed to read {0}: {1}", from, e)));
+    let name = entry.file_name().to_string_lossy().to_string();
+    try!(copy_file(from, to, &name));
+  }
+
+  Ok(())
+}
+
+fn copy_file(from: &str, to: &str, name: &str) -> Result<u64, String> {
+  fs::copy(Path::new(from).join(name), Path::new(to).join(name))
+    .map_err(|e| format!("Failed to copy {0}: {1}", name, e))
+}
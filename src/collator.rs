@@ -0,0 +1,250 @@
+extern crate libc;
+
+use wiredtiger::{Connection, c_str, get_error, report, slice_from_item,
+  string_from_config_item, string_from_ptr};
+use wiredtiger_def::{WT_COLLATOR, WT_SESSION, WT_ITEM, WT_CONFIG_ITEM};
+use event_handler::guard;
+
+use self::libc::{c_int, c_char};
+use std::cmp::Ordering;
+use std::ptr;
+
+/// Orders the keys of the tables created with `collator=name`, where `name`
+/// is the name the collator was registered with through
+/// `Connection::add_collator`.
+///
+/// Keys are passed in their packed form, so a string key includes its
+/// trailing nul byte.
+pub trait Collator: Send + Sync {
+  /// Compares the keys `left` and `right`.
+  fn compare(&self, left: &[u8], right: &[u8]) -> Result<Ordering, String>;
+
+  /// Returns a collator to use for the object `uri` instead of this one,
+  /// based on the `app_metadata` the table was created with, or `None` to
+  /// keep using this collator.
+  fn customize(&self, _uri: &str, _app_metadata: &str)
+    -> Result<Option<Box<dyn Collator>>, String> {
+    Ok(None)
+  }
+}
+
+/// Orders keys in the reverse order of their bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReverseCollator;
+
+/// Orders keys comparing their ASCII letters without regard to case. Keys
+/// differing only in case are ordered by their bytes, so they stay distinct.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CaseInsensitiveCollator;
+
+/// Orders keys comparing runs of ASCII digits by their numeric value, so
+/// `item2` comes before `item10`. Keys differing only in leading zeros are
+/// ordered by their bytes, so `item02` comes before `item2`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NumericCollator;
+
+impl Collator for ReverseCollator {
+  fn compare(&self, left: &[u8], right: &[u8]) -> Result<Ordering, String> {
+    Ok(right.cmp(left))
+  }
+}
+
+impl Collator for CaseInsensitiveCollator {
+  fn compare(&self, left: &[u8], right: &[u8]) -> Result<Ordering, String> {
+    let folded_left = left.iter().map(|b| b.to_ascii_lowercase());
+    let folded_right = right.iter().map(|b| b.to_ascii_lowercase());
+    Ok(folded_left.cmp(folded_right).then(left.cmp(right)))
+  }
+}
+
+impl Collator for NumericCollator {
+  fn compare(&self, left: &[u8], right: &[u8]) -> Result<Ordering, String> {
+    let (mut i, mut j) = (0, 0);
+
+    while i < left.len() && j < right.len() {
+      if left[i].is_ascii_digit() && right[j].is_ascii_digit() {
+        let (left_digits, next_i) = digit_run(left, i);
+        let (right_digits, next_j) = digit_run(right, j);
+        let ordering = left_digits.len().cmp(&right_digits.len())
+          .then(left_digits.cmp(right_digits));
+        if ordering != Ordering::Equal {
+          return Ok(ordering);
+        }
+        i = next_i;
+        j = next_j;
+      } else {
+        if left[i] != right[j] {
+          return Ok(left[i].cmp(&right[j]));
+        }
+        i += 1;
+        j += 1;
+      }
+    }
+
+    Ok((left.len() - i).cmp(&(right.len() - j)).then(left.cmp(right)))
+  }
+}
+
+/// Returns the digits starting at `start` without leading zeros and the
+/// position after them.
+fn digit_run(bytes: &[u8], start: usize) -> (&[u8], usize) {
+  let mut end = start;
+  while end < bytes.len() && bytes[end].is_ascii_digit() {
+    end += 1;
+  }
+  let mut first = start;
+  while first + 1 < end && bytes[first] == b'0' {
+    first += 1;
+  }
+  (&bytes[first..end], end)
+}
+
+/// Exposes a `Collator` as a `WT_COLLATOR`, laid out like `EventBridge`.
+#[repr(C)]
+struct CollatorBridge {
+  wt_collator: WT_COLLATOR,
+  collator: Box<dyn Collator>
+}
+
+impl CollatorBridge {
+  fn new(collator: Box<dyn Collator>) -> *mut CollatorBridge {
+    Box::into_raw(Box::new(CollatorBridge{
+      wt_collator: WT_COLLATOR{
+        compare: Some(compare),
+        customize: Some(customize),
+        terminate: Some(terminate)
+      },
+      collator: collator
+    }))
+  }
+}
+
+impl Connection {
+  /// Registers `collator` under `name` so tables can be created with
+  /// `TableOptions::collator(name)`. The collator lives until the
+  /// connection is closed.
+  /// # Examples
+  /// ```
+  /// connection.add_collator("case_insensitive", CaseInsensitiveCollator);
+  /// session.create_table_with_options("users",
+  ///   &TableOptions::new().collator("case_insensitive"));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the collator failed to be
+  /// registered.
+  pub fn add_collator<C>(&mut self, name: &str, collator: C)
+    -> Result<(), String> where C: Collator + 'static {
+    unsafe {
+      match (*self.wt_con).add_collator {
+        Some(add_collator) => {
+          let c_name = try!(c_str(name));
+          let bridge = CollatorBridge::new(Box::new(collator));
+          let ret = add_collator(self.wt_con,
+            c_name.as_ptr(),
+            &mut (*bridge).wt_collator,
+            ptr::null());
+
+          if ret != 0 {
+            drop(Box::from_raw(bridge));
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get add_collator".to_string())
+      }
+    }
+  }
+}
+
+extern "C" fn compare(collator: *mut WT_COLLATOR, _session: *mut WT_SESSION,
+  key1: *const WT_ITEM, key2: *const WT_ITEM, cmp: *mut c_int) -> c_int {
+  guard(|| unsafe {
+    let bridge = collator as *const CollatorBridge;
+    match (*bridge).collator.compare(slice_from_item(key1),
+      slice_from_item(key2)) {
+      Ok(ordering) => {
+        *cmp = ordering as c_int;
+        0
+      }
+      Err(message) => report("collator", message)
+    }
+  })
+}
+
+extern "C" fn customize(collator: *mut WT_COLLATOR, _session: *mut WT_SESSION,
+  uri: *const c_char, appcfg: *mut WT_CONFIG_ITEM,
+  customp: *mut *mut WT_COLLATOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = collator as *const CollatorBridge;
+    let uri = string_from_ptr(uri);
+    let app_metadata = string_from_config_item(appcfg);
+
+    match (*bridge).collator.customize(&uri, &app_metadata) {
+      Ok(Some(custom)) => {
+        *customp = &mut (*CollatorBridge::new(custom)).wt_collator;
+        0
+      }
+      Ok(None) => 0,
+      Err(message) => report("collator", message)
+    }
+  })
+}
+
+extern "C" fn terminate(collator: *mut WT_COLLATOR, _session: *mut WT_SESSION)
+  -> c_int {
+  guard(|| unsafe {
+    drop(Box::from_raw(collator as *mut CollatorBridge));
+    0
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Collator, CaseInsensitiveCollator, NumericCollator,
+    ReverseCollator};
+  use std::cmp::Ordering;
+
+  fn sorted<C: Collator>(collator: C, keys: &[&str]) -> Vec<String> {
+    let mut keys: Vec<&str> = keys.to_vec();
+    keys.sort_by(|a, b| collator.compare(a.as_bytes(), b.as_bytes()).unwrap());
+    keys.iter().map(|k| k.to_string()).collect()
+  }
+
+  #[test]
+  fn numeric_collator_compares_digit_runs_by_value() {
+    assert_eq!(sorted(NumericCollator, &["item10", "item2", "item1", "item"]),
+      vec!["item", "item1", "item2", "item10"]);
+    assert_eq!(sorted(NumericCollator, &["b1", "a20", "a3x", "a3"]),
+      vec!["a3", "a3x", "a20", "b1"]);
+    assert_eq!(NumericCollator.compare(b"item2\0", b"item10\0"),
+      Ok(Ordering::Less));
+  }
+
+  #[test]
+  fn numeric_collator_breaks_ties_on_leading_zeros() {
+    assert_eq!(NumericCollator.compare(b"item02", b"item2"),
+      Ok(Ordering::Less));
+    assert_eq!(NumericCollator.compare(b"item2", b"item02"),
+      Ok(Ordering::Greater));
+    assert_eq!(NumericCollator.compare(b"0", b"000"), Ok(Ordering::Less));
+    assert_eq!(NumericCollator.compare(b"item02", b"item02"),
+      Ok(Ordering::Equal));
+  }
+
+  #[test]
+  fn case_insensitive_collator_breaks_ties_on_bytes() {
+    assert_eq!(sorted(CaseInsensitiveCollator, &["b", "a", "B", "A"]),
+      vec!["A", "a", "B", "b"]);
+    assert_eq!(CaseInsensitiveCollator.compare(b"Alice", b"alice"),
+      Ok(Ordering::Less));
+    assert_eq!(CaseInsensitiveCollator.compare(b"alice", b"alice"),
+      Ok(Ordering::Equal));
+  }
+
+  #[test]
+  fn reverse_collator_reverses_byte_order() {
+    assert_eq!(sorted(ReverseCollator, &["a", "c", "b"]),
+      vec!["c", "b", "a"]);
+  }
+}
@@ -0,0 +1,345 @@
+extern crate libc;
+
+use wiredtiger::{Connection, c_str, get_error, report};
+use wiredtiger_def::{WT_COMPRESSOR, WT_SESSION};
+use event_handler::guard;
+
+use self::libc::{c_int, size_t};
+use std::{ptr, slice};
+
+/// Compresses the blocks of the tables created with `block_compressor=name`,
+/// where `name` is the name the compressor was registered with through
+/// `Connection::add_compressor`.
+pub trait Compressor: Send + Sync {
+  /// Compresses `src` into `dst` and returns the compressed length, or
+  /// `None` when the result does not fit in `dst` and the block should be
+  /// written uncompressed.
+  fn compress(&self, src: &[u8], dst: &mut [u8])
+    -> Result<Option<usize>, String>;
+
+  /// Decompresses `src` into `dst` and returns the decompressed length.
+  fn decompress(&self, src: &[u8], dst: &mut [u8]) -> Result<usize, String>;
+
+  /// Returns the size of the buffer `compress` needs for `src`, or `None`
+  /// for a buffer as large as `src`.
+  fn pre_size(&self, _src: &[u8]) -> Result<Option<usize>, String> {
+    Ok(None)
+  }
+
+  /// Returns `true` when the compressor implements `compress_raw`.
+  fn supports_raw(&self) -> bool {
+    false
+  }
+
+  /// Compresses as many leading records of `src` as fit in `dst`, leaving
+  /// `extra` bytes of `dst` unused, and returns the compressed length and
+  /// the number of records compressed, or `None` to write the page
+  /// uncompressed. `offsets` holds the boundaries of the records in `src`,
+  /// one more than the number of records. When `last` is `true` the records
+  /// are the end of the page.
+  /// Only called when `supports_raw` returns `true`.
+  fn compress_raw(&self, _page_max: usize, _split_pct: i32, _extra: usize,
+    _src: &[u8], _offsets: &[u32], _dst: &mut [u8], _last: bool)
+    -> Result<Option<(usize, u32)>, String> {
+    Err("compress_raw is not supported".to_string())
+  }
+}
+
+/// Exposes a `Compressor` as a `WT_COMPRESSOR`, laid out like `EventBridge`.
+#[repr(C)]
+struct CompressorBridge {
+  wt_compressor: WT_COMPRESSOR,
+  compressor: Box<dyn Compressor>
+}
+
+impl CompressorBridge {
+  fn new<C>(compressor: C) -> Box<CompressorBridge>
+    where C: Compressor + 'static {
+    let compress_raw = if compressor.supports_raw() {
+      Some(compress_raw as extern "C" fn(_, _, _, _, _, _, _, _, _, _, _, _,
+        _) -> _)
+    } else {
+      None
+    };
+    Box::new(CompressorBridge{
+      wt_compressor: WT_COMPRESSOR{
+        compress: Some(compress),
+        compress_raw: compress_raw,
+        decompress: Some(decompress),
+        pre_size: Some(pre_size),
+        terminate: Some(terminate)
+      },
+      compressor: Box::new(compressor)
+    })
+  }
+}
+
+impl Connection {
+  /// Registers `compressor` under `name` so tables can be created with
+  /// `TableOptions::block_compressor(name)`. The compressor lives until the
+  /// connection is closed.
+  /// # Examples
+  /// ```
+  /// connection.add_compressor("lz4", Lz4Compressor);
+  /// session.create_table_with_options("users",
+  ///   &TableOptions::new().block_compressor("lz4"));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the compressor failed to be
+  /// registered.
+  pub fn add_compressor<C>(&mut self, name: &str, compressor: C)
+    -> Result<(), String> where C: Compressor + 'static {
+    unsafe {
+      match (*self.wt_con).add_compressor {
+        Some(add_compressor) => {
+          let c_name = try!(c_str(name));
+          let bridge = Box::into_raw(CompressorBridge::new(compressor));
+          let ret = add_compressor(self.wt_con,
+            c_name.as_ptr(),
+            &mut (*bridge).wt_compressor,
+            ptr::null());
+
+          if ret != 0 {
+            drop(Box::from_raw(bridge));
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get add_compressor".to_string())
+      }
+    }
+  }
+}
+
+unsafe fn compressor<'a>(wt_compressor: *mut WT_COMPRESSOR)
+  -> &'a dyn Compressor {
+  &*(*(wt_compressor as *const CompressorBridge)).compressor
+}
+
+extern "C" fn compress(wt_compressor: *mut WT_COMPRESSOR,
+  _session: *mut WT_SESSION, src: *mut u8, src_len: size_t, dst: *mut u8,
+  dst_len: size_t, result_lenp: *mut size_t,
+  compression_failed: *mut c_int) -> c_int {
+  guard(|| unsafe {
+    let src = slice::from_raw_parts(src, src_len as usize);
+    let dst = slice::from_raw_parts_mut(dst, dst_len as usize);
+    match compressor(wt_compressor).compress(src, dst) {
+      Ok(Some(len)) if len > dst.len() => report("compressor",
+        format!("compress returned {0} bytes for a {1} byte buffer", len,
+          dst.len())),
+      Ok(Some(len)) => {
+        *result_lenp = len as size_t;
+        *compression_failed = 0;
+        0
+      }
+      Ok(None) => {
+        *compression_failed = 1;
+        0
+      }
+      Err(message) => report("compressor", message)
+    }
+  })
+}
+
+extern "C" fn compress_raw(wt_compressor: *mut WT_COMPRESSOR,
+  _session: *mut WT_SESSION, page_max: size_t, split_pct: c_int,
+  extra: size_t, src: *mut u8, offsets: *mut u32, slots: u32, dst: *mut u8,
+  dst_len: size_t, last: c_int, result_lenp: *mut size_t,
+  result_slotsp: *mut u32) -> c_int {
+  guard(|| unsafe {
+    let offsets = slice::from_raw_parts(offsets, slots as usize + 1);
+    let src = slice::from_raw_parts(src, offsets[slots as usize] as usize);
+    let dst = slice::from_raw_parts_mut(dst, dst_len as usize);
+    match compressor(wt_compressor).compress_raw(page_max as usize, split_pct,
+      extra as usize, src, offsets, dst, last != 0) {
+      Ok(Some((len, compressed_slots)))
+        if len > dst.len() || compressed_slots > slots => report("compressor",
+        format!("compress_raw returned {0} bytes of {1} records for a {2} \
+          byte buffer of {3} records", len, compressed_slots, dst.len(),
+          slots)),
+      Ok(Some((len, compressed_slots))) => {
+        *result_lenp = len as size_t;
+        *result_slotsp = compressed_slots;
+        0
+      }
+      Ok(None) => {
+        *result_slotsp = 0;
+        0
+      }
+      Err(message) => report("compressor", message)
+    }
+  })
+}
+
+extern "C" fn decompress(wt_compressor: *mut WT_COMPRESSOR,
+  _session: *mut WT_SESSION, src: *mut u8, src_len: size_t, dst: *mut u8,
+  dst_len: size_t, result_lenp: *mut size_t) -> c_int {
+  guard(|| unsafe {
+    let src = slice::from_raw_parts(src, src_len as usize);
+    let dst = slice::from_raw_parts_mut(dst, dst_len as usize);
+    match compressor(wt_compressor).decompress(src, dst) {
+      Ok(len) if len > dst.len() => report("compressor",
+        format!("decompress returned {0} bytes for a {1} byte buffer", len,
+          dst.len())),
+      Ok(len) => {
+        *result_lenp = len as size_t;
+        0
+      }
+      Err(message) => report("compressor", message)
+    }
+  })
+}
+
+extern "C" fn pre_size(wt_compressor: *mut WT_COMPRESSOR,
+  _session: *mut WT_SESSION, src: *mut u8, src_len: size_t,
+  result_lenp: *mut size_t) -> c_int {
+  guard(|| unsafe {
+    let src = slice::from_raw_parts(src, src_len as usize);
+    match compressor(wt_compressor).pre_size(src) {
+      Ok(len) => {
+        *result_lenp = len.unwrap_or(src.len()) as size_t;
+        0
+      }
+      Err(message) => report("compressor", message)
+    }
+  })
+}
+
+extern "C" fn terminate(wt_compressor: *mut WT_COMPRESSOR,
+  _session: *mut WT_SESSION) -> c_int {
+  guard(|| unsafe {
+    drop(Box::from_raw(wt_compressor as *mut CompressorBridge));
+    0
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Compressor, CompressorBridge, compress, compress_raw,
+    decompress, pre_size};
+  use event_handler::WT_ERROR;
+  use wiredtiger_def::WT_COMPRESSOR;
+
+  use std::ptr;
+
+  /// Copies its input, returning `len` as the output length when set.
+  struct CopyCompressor {
+    len: Option<usize>,
+    raw: bool
+  }
+
+  impl Compressor for CopyCompressor {
+    fn compress(&self, src: &[u8], dst: &mut [u8])
+      -> Result<Option<usize>, String> {
+      if src.len() > dst.len() {
+        return Ok(None);
+      }
+      dst[..src.len()].copy_from_slice(src);
+      Ok(Some(self.len.unwrap_or(src.len())))
+    }
+
+    fn decompress(&self, src: &[u8], dst: &mut [u8])
+      -> Result<usize, String> {
+      dst[..src.len()].copy_from_slice(src);
+      Ok(self.len.unwrap_or(src.len()))
+    }
+
+    fn pre_size(&self, src: &[u8]) -> Result<Option<usize>, String> {
+      Ok(self.len.map(|_| src.len() * 2))
+    }
+
+    fn supports_raw(&self) -> bool {
+      self.raw
+    }
+
+    /// Copies the records that fit in `dst`.
+    fn compress_raw(&self, _page_max: usize, _split_pct: i32, _extra: usize,
+      src: &[u8], offsets: &[u32], dst: &mut [u8], _last: bool)
+      -> Result<Option<(usize, u32)>, String> {
+      assert_eq!(offsets[offsets.len() - 1] as usize, src.len());
+      let slots = offsets.iter().rposition(|o| *o as usize <= dst.len())
+        .unwrap() as u32;
+      let len = offsets[slots as usize] as usize;
+      dst[..len].copy_from_slice(&src[..len]);
+      Ok(Some((self.len.unwrap_or(len), slots)))
+    }
+  }
+
+  fn bridge(len: Option<usize>) -> Box<CompressorBridge> {
+    CompressorBridge::new(CopyCompressor{ len: len, raw: true })
+  }
+
+  fn wt_compressor(bridge: &mut CompressorBridge) -> *mut WT_COMPRESSOR {
+    &mut bridge.wt_compressor
+  }
+
+  #[test]
+  fn compress_raw_is_exposed_when_supported() {
+    assert!(bridge(None).wt_compressor.compress_raw.is_some());
+    let plain = CompressorBridge::new(CopyCompressor{ len: None, raw: false });
+    assert!(plain.wt_compressor.compress_raw.is_none());
+  }
+
+  #[test]
+  fn compress_and_decompress_use_the_buffers_given() {
+    let mut bridge = bridge(None);
+    let mut src = *b"records";
+    let mut dst = [0u8; 8];
+    let mut len = 0;
+    let mut failed = 1;
+    assert_eq!(compress(wt_compressor(&mut bridge), ptr::null_mut(),
+      src.as_mut_ptr(), 7, dst.as_mut_ptr(), 8, &mut len, &mut failed), 0);
+    assert_eq!((len, failed), (7, 0));
+    assert_eq!(&dst[..7], b"records");
+
+    let mut small = [0u8; 4];
+    assert_eq!(compress(wt_compressor(&mut bridge), ptr::null_mut(),
+      src.as_mut_ptr(), 7, small.as_mut_ptr(), 4, &mut len, &mut failed), 0);
+    assert_eq!(failed, 1);
+
+    let mut out = [0u8; 7];
+    assert_eq!(decompress(wt_compressor(&mut bridge), ptr::null_mut(),
+      dst.as_mut_ptr(), 7, out.as_mut_ptr(), 7, &mut len), 0);
+    assert_eq!((len, &out), (7, b"records"));
+
+    assert_eq!(pre_size(wt_compressor(&mut bridge), ptr::null_mut(),
+      src.as_mut_ptr(), 7, &mut len), 0);
+    assert_eq!(len, 7);
+  }
+
+  #[test]
+  fn lengths_past_the_buffer_are_rejected() {
+    let mut bridge = bridge(Some(9));
+    let mut src = *b"records";
+    let mut dst = [0u8; 8];
+    let mut len = 0;
+    let mut failed = 0;
+    assert_eq!(compress(wt_compressor(&mut bridge), ptr::null_mut(),
+      src.as_mut_ptr(), 7, dst.as_mut_ptr(), 8, &mut len, &mut failed),
+      WT_ERROR);
+    assert_eq!(decompress(wt_compressor(&mut bridge), ptr::null_mut(),
+      src.as_mut_ptr(), 7, dst.as_mut_ptr(), 8, &mut len), WT_ERROR);
+    assert_eq!(pre_size(wt_compressor(&mut bridge), ptr::null_mut(),
+      src.as_mut_ptr(), 7, &mut len), 0);
+    assert_eq!(len, 14);
+  }
+
+  #[test]
+  fn compress_raw_sees_the_records_of_the_page() {
+    let mut src = *b"aaabbbbcc";
+    let mut offsets = [0u32, 3, 7, 9];
+    let mut dst = [0u8; 8];
+    let mut len = 0;
+    let mut slots = 0;
+    assert_eq!(compress_raw(wt_compressor(&mut bridge(None)),
+      ptr::null_mut(), 0, 90, 0, src.as_mut_ptr(), offsets.as_mut_ptr(), 3,
+      dst.as_mut_ptr(), 8, 1, &mut len, &mut slots), 0);
+    assert_eq!((len, slots), (7, 2));
+    assert_eq!(&dst[..7], b"aaabbbb");
+
+    assert_eq!(compress_raw(wt_compressor(&mut bridge(Some(9))),
+      ptr::null_mut(), 0, 90, 0, src.as_mut_ptr(), offsets.as_mut_ptr(), 3,
+      dst.as_mut_ptr(), 8, 1, &mut len, &mut slots), WT_ERROR);
+  }
+}
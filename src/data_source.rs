@@ -0,0 +1,624 @@
+extern crate libc;
+
+use wiredtiger::{Connection, WT_DUPLICATE_KEY, WT_NOTFOUND, c_str, get_error,
+  report, slice_from_item, item_from_bytes, string_from_ptr};
+use extension::ExtensionConfig;
+use wiredtiger_def::{WT_DATA_SOURCE, WT_SESSION, WT_CURSOR, WT_CONFIG_ARG};
+use event_handler::guard;
+
+use self::libc::{c_int, c_char};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::collections::Bound::{Excluded, Included, Unbounded};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+/// A record returned by a `DataSourceCursor`, as packed key and value bytes.
+pub type Record = (Vec<u8>, Vec<u8>);
+
+/// Stores the objects whose URI starts with the prefix the data source was
+/// registered with through `Connection::add_data_source`.
+///
+/// Tables are stored in a data source by creating them with
+/// `TableOptions::data_source`, after which they are used through the usual
+/// `Session` and `Cursor` API.
+pub trait DataSource: Send + Sync {
+  /// Creates the object `uri`.
+  fn create(&self, uri: &str) -> Result<(), String>;
+
+  /// Opens a cursor on the object `uri`.
+  fn open_cursor(&self, uri: &str)
+    -> Result<Box<dyn DataSourceCursor>, String>;
+
+  /// Drops the object `uri`.
+  fn drop(&self, uri: &str) -> Result<(), String>;
+
+  /// Renames the object `uri` to `new_uri`.
+  fn rename(&self, uri: &str, new_uri: &str) -> Result<(), String>;
+
+  /// Removes every record from the object `uri`.
+  fn truncate(&self, uri: &str) -> Result<(), String>;
+
+  /// Checks the integrity of the object `uri`.
+  fn verify(&self, _uri: &str) -> Result<(), String> {
+    Ok(())
+  }
+
+  /// Makes the objects of the data source durable.
+  fn checkpoint(&self) -> Result<(), String> {
+    Ok(())
+  }
+
+  /// Called once when the connection is closed.
+  fn terminate(&self) {}
+}
+
+/// A cursor opened by a `DataSource`. Keys and values are in their packed
+/// form, so a string includes its trailing nul byte.
+pub trait DataSourceCursor: Send {
+  /// Moves to the next record, or the first one when the cursor is not
+  /// positioned, and returns it, or `None` at the end of the object.
+  fn next(&mut self) -> Result<Option<Record>, String>;
+
+  /// Moves to the previous record, or the last one when the cursor is not
+  /// positioned, and returns it, or `None` at the start of the object.
+  fn prev(&mut self) -> Result<Option<Record>, String>;
+
+  /// Leaves the cursor without a position.
+  fn reset(&mut self) -> Result<(), String>;
+
+  /// Moves to `key` and returns its value, or `None` if it does not exist.
+  fn search(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, String>;
+
+  /// Moves to `key` or the closest record to it and returns that record and
+  /// how its key compares to `key`, or `None` if the object is empty.
+  fn search_near(&mut self, key: &[u8])
+    -> Result<Option<(Record, Ordering)>, String>;
+
+  /// Stores `value` for `key`. An existing value is replaced when
+  /// `overwrite` is `true`, the cursor's `CursorOptions::overwrite`, and
+  /// otherwise kept, returning `false`.
+  fn insert(&mut self, key: &[u8], value: &[u8], overwrite: bool)
+    -> Result<bool, String>;
+
+  /// Replaces the value of the existing `key` and returns `false` if it
+  /// does not exist.
+  fn update(&mut self, key: &[u8], value: &[u8]) -> Result<bool, String>;
+
+  /// Removes `key` and returns `false` if it does not exist.
+  fn remove(&mut self, key: &[u8]) -> Result<bool, String>;
+}
+
+type MemoryTable = Arc<Mutex<BTreeMap<Vec<u8>, Vec<u8>>>>;
+
+/// A `DataSource` that keeps its objects in memory, lost when the
+/// connection is closed.
+/// # Examples
+/// ```
+/// connection.add_data_source("memory:", MemoryDataSource::new());
+/// session.create_table_with_options("sessions",
+///   &TableOptions::new().data_source("memory"));
+/// let cursor = session.open_cursor("sessions");
+/// ```
+#[derive(Default)]
+pub struct MemoryDataSource {
+  objects: Mutex<BTreeMap<String, MemoryTable>>
+}
+
+/// A cursor on an object of a `MemoryDataSource`.
+pub struct MemoryCursor {
+  table: MemoryTable,
+  position: Option<Vec<u8>>
+}
+
+impl MemoryDataSource {
+  pub fn new() -> MemoryDataSource {
+    MemoryDataSource::default()
+  }
+
+  fn object(&self, uri: &str) -> Result<MemoryTable, String> {
+    let objects = self.objects.lock().unwrap();
+    objects.get(uri).cloned().ok_or(format!("{0} does not exist", uri))
+  }
+}
+
+impl DataSource for MemoryDataSource {
+  fn create(&self, uri: &str) -> Result<(), String> {
+    let mut objects = self.objects.lock().unwrap();
+    objects.entry(uri.to_string()).or_insert_with(MemoryTable::default);
+    Ok(())
+  }
+
+  fn open_cursor(&self, uri: &str)
+    -> Result<Box<dyn DataSourceCursor>, String> {
+    Ok(Box::new(MemoryCursor{
+      table: try!(self.object(uri)),
+      position: None
+    }))
+  }
+
+  fn drop(&self, uri: &str) -> Result<(), String> {
+    let mut objects = self.objects.lock().unwrap();
+    objects.remove(uri).map(|_| ()).ok_or(format!("{0} does not exist", uri))
+  }
+
+  fn rename(&self, uri: &str, new_uri: &str) -> Result<(), String> {
+    let mut objects = self.objects.lock().unwrap();
+    if objects.contains_key(new_uri) {
+      return Err(format!("{0} already exists", new_uri));
+    }
+    match objects.remove(uri) {
+      Some(table) => {
+        objects.insert(new_uri.to_string(), table);
+        Ok(())
+      }
+      None => Err(format!("{0} does not exist", uri))
+    }
+  }
+
+  fn truncate(&self, uri: &str) -> Result<(), String> {
+    let table = try!(self.object(uri));
+    table.lock().unwrap().clear();
+    Ok(())
+  }
+}
+
+impl MemoryCursor {
+  fn move_to(&mut self, record: Option<(&Vec<u8>, &Vec<u8>)>)
+    -> Option<Record> {
+    let record = record.map(|(k, v)| (k.clone(), v.clone()));
+    self.position = record.as_ref().map(|&(ref k, _)| k.clone());
+    record
+  }
+}
+
+impl DataSourceCursor for MemoryCursor {
+  fn next(&mut self) -> Result<Option<Record>, String> {
+    let table = self.table.clone();
+    let table = table.lock().unwrap();
+    let record = match self.position {
+      Some(ref key) => table.range::<Vec<u8>, _>((Excluded(key), Unbounded))
+        .next(),
+      None => table.iter().next()
+    };
+    Ok(self.move_to(record))
+  }
+
+  fn prev(&mut self) -> Result<Option<Record>, String> {
+    let table = self.table.clone();
+    let table = table.lock().unwrap();
+    let record = match self.position {
+      Some(ref key) => table.range::<Vec<u8>, _>((Unbounded, Excluded(key)))
+        .next_back(),
+      None => table.iter().next_back()
+    };
+    Ok(self.move_to(record))
+  }
+
+  fn reset(&mut self) -> Result<(), String> {
+    self.position = None;
+    Ok(())
+  }
+
+  fn search(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let value = self.table.lock().unwrap().get(key).cloned();
+    self.position = value.as_ref().map(|_| key.to_vec());
+    Ok(value)
+  }
+
+  fn search_near(&mut self, key: &[u8])
+    -> Result<Option<(Record, Ordering)>, String> {
+    let table = self.table.clone();
+    let table = table.lock().unwrap();
+    let after = table.range::<[u8], _>((Included(key), Unbounded)).next();
+    let record = match after {
+      Some(record) => Some(record),
+      None => table.iter().next_back()
+    };
+
+    Ok(self.move_to(record).map(|record| {
+      let ordering = record.0[..].cmp(key);
+      (record, ordering)
+    }))
+  }
+
+  fn insert(&mut self, key: &[u8], value: &[u8], overwrite: bool)
+    -> Result<bool, String> {
+    let mut table = self.table.lock().unwrap();
+    if !overwrite && table.contains_key(key) {
+      return Ok(false);
+    }
+    table.insert(key.to_vec(), value.to_vec());
+    self.position = Some(key.to_vec());
+    Ok(true)
+  }
+
+  fn update(&mut self, key: &[u8], value: &[u8]) -> Result<bool, String> {
+    let mut table = self.table.lock().unwrap();
+    match table.get_mut(key) {
+      Some(existing) => {
+        *existing = value.to_vec();
+        self.position = Some(key.to_vec());
+        Ok(true)
+      }
+      None => Ok(false)
+    }
+  }
+
+  fn remove(&mut self, key: &[u8]) -> Result<bool, String> {
+    let removed = self.table.lock().unwrap().remove(key).is_some();
+    self.position = None;
+    Ok(removed)
+  }
+}
+
+/// Exposes a `DataSource` as a `WT_DATA_SOURCE`, laid out like
+/// `EventBridge`.
+#[repr(C)]
+struct DataSourceBridge {
+  wt_data_source: WT_DATA_SOURCE,
+  data_source: Box<dyn DataSource>
+}
+
+/// Exposes a `DataSourceCursor` as a `WT_CURSOR`, laid out like
+/// `EventBridge`. Wired Tiger reads the current record from `wt_cursor.key`
+/// and `wt_cursor.value`, which point into `key` and `value`.
+#[repr(C)]
+struct CursorBridge {
+  wt_cursor: WT_CURSOR,
+  cursor: Box<dyn DataSourceCursor>,
+  overwrite: bool,
+  key: Vec<u8>,
+  value: Vec<u8>
+}
+
+impl Connection {
+  /// Registers `data_source` to store the objects whose URI starts with
+  /// `prefix`, such as `memory:`. The data source lives until the
+  /// connection is closed.
+  /// # Examples
+  /// ```
+  /// connection.add_data_source("memory:", MemoryDataSource::new());
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the data source failed to be
+  /// registered.
+  pub fn add_data_source<D>(&mut self, prefix: &str, data_source: D)
+    -> Result<(), String> where D: DataSource + 'static {
+    unsafe {
+      match (*self.wt_con).add_data_source {
+        Some(add_data_source) => {
+          let c_prefix = try!(c_str(prefix));
+          let mut wt_data_source = WT_DATA_SOURCE::default();
+          wt_data_source.create = Some(ds_create);
+          wt_data_source.drop = Some(ds_drop);
+          wt_data_source.open_cursor = Some(ds_open_cursor);
+          wt_data_source.rename = Some(ds_rename);
+          wt_data_source.truncate = Some(ds_truncate);
+          wt_data_source.verify = Some(ds_verify);
+          wt_data_source.checkpoint = Some(ds_checkpoint);
+          wt_data_source.terminate = Some(ds_terminate);
+          let bridge = Box::into_raw(Box::new(DataSourceBridge{
+            wt_data_source: wt_data_source,
+            data_source: Box::new(data_source)
+          }));
+          let ret = add_data_source(self.wt_con,
+            c_prefix.as_ptr(),
+            &mut (*bridge).wt_data_source,
+            ptr::null());
+
+          if ret != 0 {
+            drop(Box::from_raw(bridge));
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get add_data_source".to_string())
+      }
+    }
+  }
+}
+
+fn to_code(result: Result<(), String>) -> c_int {
+  match result {
+    Ok(()) => 0,
+    Err(message) => report("data source", message)
+  }
+}
+
+unsafe fn data_source<'a>(dsrc: *mut WT_DATA_SOURCE) -> &'a dyn DataSource {
+  &*(*(dsrc as *const DataSourceBridge)).data_source
+}
+
+extern "C" fn ds_create(dsrc: *mut WT_DATA_SOURCE, _session: *mut WT_SESSION,
+  uri: *const c_char, _config: *mut WT_CONFIG_ARG) -> c_int {
+  guard(|| unsafe { to_code(data_source(dsrc).create(&string_from_ptr(uri))) })
+}
+
+extern "C" fn ds_drop(dsrc: *mut WT_DATA_SOURCE, _session: *mut WT_SESSION,
+  uri: *const c_char, _config: *mut WT_CONFIG_ARG) -> c_int {
+  guard(|| unsafe { to_code(data_source(dsrc).drop(&string_from_ptr(uri))) })
+}
+
+extern "C" fn ds_rename(dsrc: *mut WT_DATA_SOURCE, _session: *mut WT_SESSION,
+  uri: *const c_char, newuri: *const c_char, _config: *mut WT_CONFIG_ARG)
+  -> c_int {
+  guard(|| unsafe {
+    to_code(data_source(dsrc).rename(&string_from_ptr(uri),
+      &string_from_ptr(newuri)))
+  })
+}
+
+extern "C" fn ds_truncate(dsrc: *mut WT_DATA_SOURCE, _session: *mut WT_SESSION,
+  uri: *const c_char, _config: *mut WT_CONFIG_ARG) -> c_int {
+  guard(|| unsafe {
+    to_code(data_source(dsrc).truncate(&string_from_ptr(uri)))
+  })
+}
+
+extern "C" fn ds_verify(dsrc: *mut WT_DATA_SOURCE, _session: *mut WT_SESSION,
+  uri: *const c_char, _config: *mut WT_CONFIG_ARG) -> c_int {
+  guard(|| unsafe { to_code(data_source(dsrc).verify(&string_from_ptr(uri))) })
+}
+
+extern "C" fn ds_checkpoint(dsrc: *mut WT_DATA_SOURCE,
+  _session: *mut WT_SESSION, _config: *mut WT_CONFIG_ARG) -> c_int {
+  guard(|| unsafe { to_code(data_source(dsrc).checkpoint()) })
+}
+
+extern "C" fn ds_terminate(dsrc: *mut WT_DATA_SOURCE,
+  _session: *mut WT_SESSION) -> c_int {
+  guard(|| unsafe {
+    let bridge = Box::from_raw(dsrc as *mut DataSourceBridge);
+    bridge.data_source.terminate();
+    0
+  })
+}
+
+extern "C" fn ds_open_cursor(dsrc: *mut WT_DATA_SOURCE,
+  session: *mut WT_SESSION, uri: *const c_char, config: *mut WT_CONFIG_ARG,
+  new_cursor: *mut *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let overwrite = match overwrite(session, config) {
+      Ok(overwrite) => overwrite,
+      Err(message) => return report("data source", message)
+    };
+    let cursor = match data_source(dsrc).open_cursor(&string_from_ptr(uri)) {
+      Ok(cursor) => cursor,
+      Err(message) => return report("data source", message)
+    };
+
+    let mut wt_cursor = WT_CURSOR::default();
+    wt_cursor.next = Some(cursor_next);
+    wt_cursor.prev = Some(cursor_prev);
+    wt_cursor.reset = Some(cursor_reset);
+    wt_cursor.search = Some(cursor_search);
+    wt_cursor.search_near = Some(cursor_search_near);
+    wt_cursor.insert = Some(cursor_insert);
+    wt_cursor.update = Some(cursor_update);
+    wt_cursor.remove = Some(cursor_remove);
+    wt_cursor.close = Some(cursor_close);
+
+    let bridge = Box::into_raw(Box::new(CursorBridge{
+      wt_cursor: wt_cursor,
+      cursor: cursor,
+      overwrite: overwrite,
+      key: Vec::new(),
+      value: Vec::new()
+    }));
+    *new_cursor = &mut (*bridge).wt_cursor;
+    0
+  })
+}
+
+/// Returns whether the cursor opened with `config` replaces existing values
+/// on insert, which Wired Tiger does by default.
+unsafe fn overwrite(session: *mut WT_SESSION, config: *mut WT_CONFIG_ARG)
+  -> Result<bool, String> {
+  let connection = Connection::from_raw((*session).connection);
+  let api = try!(connection.extension_api());
+  let config = ExtensionConfig{ wt_config: config };
+
+  match try!(api.config_get(&config, "overwrite")) {
+    Some(value) => Ok(value != "false" && value != "0"),
+    None => Ok(true)
+  }
+}
+
+unsafe fn cursor_bridge<'a>(cursor: *mut WT_CURSOR) -> &'a mut CursorBridge {
+  &mut *(cursor as *mut CursorBridge)
+}
+
+/// Makes `record` the current record of the cursor, or returns
+/// `WT_NOTFOUND` when there is none.
+fn set_record(bridge: &mut CursorBridge, record: Option<Record>) -> c_int {
+  match record {
+    Some((key, value)) => {
+      bridge.key = key;
+      bridge.value = value;
+      bridge.wt_cursor.key = item_from_bytes(&bridge.key);
+      bridge.wt_cursor.value = item_from_bytes(&bridge.value);
+      0
+    }
+    None => WT_NOTFOUND
+  }
+}
+
+extern "C" fn cursor_next(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    match bridge.cursor.next() {
+      Ok(record) => set_record(bridge, record),
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_prev(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    match bridge.cursor.prev() {
+      Ok(record) => set_record(bridge, record),
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_reset(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe { to_code(cursor_bridge(cursor).cursor.reset()) })
+}
+
+extern "C" fn cursor_search(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    let key = slice_from_item(&bridge.wt_cursor.key).to_vec();
+    match bridge.cursor.search(&key) {
+      Ok(value) => set_record(bridge, value.map(|value| (key, value))),
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_search_near(cursor: *mut WT_CURSOR, exactp: *mut c_int)
+  -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    let key = slice_from_item(&bridge.wt_cursor.key).to_vec();
+    match bridge.cursor.search_near(&key) {
+      Ok(Some((record, ordering))) => {
+        *exactp = ordering as c_int;
+        set_record(bridge, Some(record))
+      }
+      Ok(None) => WT_NOTFOUND,
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_insert(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    let key = slice_from_item(&bridge.wt_cursor.key);
+    let value = slice_from_item(&bridge.wt_cursor.value);
+    match bridge.cursor.insert(key, value, bridge.overwrite) {
+      Ok(true) => 0,
+      Ok(false) => WT_DUPLICATE_KEY,
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_update(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    let key = slice_from_item(&bridge.wt_cursor.key);
+    let value = slice_from_item(&bridge.wt_cursor.value);
+    match bridge.cursor.update(key, value) {
+      Ok(true) => 0,
+      Ok(false) => WT_NOTFOUND,
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_remove(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = cursor_bridge(cursor);
+    let key = slice_from_item(&bridge.wt_cursor.key);
+    match bridge.cursor.remove(key) {
+      Ok(true) => 0,
+      Ok(false) => WT_NOTFOUND,
+      Err(message) => report("data source", message)
+    }
+  })
+}
+
+extern "C" fn cursor_close(cursor: *mut WT_CURSOR) -> c_int {
+  guard(|| unsafe {
+    drop(Box::from_raw(cursor as *mut CursorBridge));
+    0
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{DataSource, MemoryDataSource};
+  use std::cmp::Ordering;
+
+  #[test]
+  fn memory_cursor_inserts_and_iterates_in_key_order() {
+    let source = MemoryDataSource::new();
+    source.create("memory:users").unwrap();
+    let mut cursor = source.open_cursor("memory:users").unwrap();
+
+    assert_eq!(cursor.insert(b"bob", b"2", true), Ok(true));
+    assert_eq!(cursor.insert(b"alice", b"1", true), Ok(true));
+    assert_eq!(cursor.insert(b"alice", b"3", false), Ok(false));
+    assert_eq!(cursor.search(b"alice"), Ok(Some(b"1".to_vec())));
+    assert_eq!(cursor.insert(b"alice", b"3", true), Ok(true));
+
+    cursor.reset().unwrap();
+    assert_eq!(cursor.next(), Ok(Some((b"alice".to_vec(), b"3".to_vec()))));
+    assert_eq!(cursor.next(), Ok(Some((b"bob".to_vec(), b"2".to_vec()))));
+    assert_eq!(cursor.next(), Ok(None));
+    assert_eq!(cursor.prev(), Ok(Some((b"bob".to_vec(), b"2".to_vec()))));
+  }
+
+  #[test]
+  fn memory_cursor_searches_near_keys() {
+    let source = MemoryDataSource::new();
+    source.create("memory:users").unwrap();
+    let mut cursor = source.open_cursor("memory:users").unwrap();
+    assert_eq!(cursor.search_near(b"a"), Ok(None));
+
+    cursor.insert(b"b", b"1", true).unwrap();
+    cursor.insert(b"d", b"2", true).unwrap();
+    let near = |key: &[u8]| cursor_near(&source, key);
+    assert_eq!(near(b"b"), (b"b".to_vec(), Ordering::Equal));
+    assert_eq!(near(b"c"), (b"d".to_vec(), Ordering::Greater));
+    assert_eq!(near(b"e"), (b"d".to_vec(), Ordering::Less));
+  }
+
+  fn cursor_near(source: &MemoryDataSource, key: &[u8])
+    -> (Vec<u8>, Ordering) {
+    let mut cursor = source.open_cursor("memory:users").unwrap();
+    let ((found, _), ordering) = cursor.search_near(key).unwrap().unwrap();
+    (found, ordering)
+  }
+
+  #[test]
+  fn memory_cursor_updates_and_removes_existing_keys() {
+    let source = MemoryDataSource::new();
+    source.create("memory:users").unwrap();
+    let mut cursor = source.open_cursor("memory:users").unwrap();
+
+    assert_eq!(cursor.update(b"alice", b"1"), Ok(false));
+    cursor.insert(b"alice", b"1", true).unwrap();
+    assert_eq!(cursor.update(b"alice", b"2"), Ok(true));
+    assert_eq!(cursor.search(b"alice"), Ok(Some(b"2".to_vec())));
+    assert_eq!(cursor.remove(b"alice"), Ok(true));
+    assert_eq!(cursor.remove(b"alice"), Ok(false));
+    assert_eq!(cursor.search(b"alice"), Ok(None));
+  }
+
+  #[test]
+  fn memory_data_source_renames_truncates_and_drops() {
+    let source = MemoryDataSource::new();
+    source.create("memory:users").unwrap();
+    source.create("memory:admins").unwrap();
+    source.open_cursor("memory:users").unwrap()
+      .insert(b"alice", b"1", true).unwrap();
+
+    assert!(source.rename("memory:users", "memory:admins").is_err());
+    source.rename("memory:users", "memory:customers").unwrap();
+    assert!(source.open_cursor("memory:users").is_err());
+    let mut cursor = source.open_cursor("memory:customers").unwrap();
+    assert_eq!(cursor.search(b"alice"), Ok(Some(b"1".to_vec())));
+
+    source.truncate("memory:customers").unwrap();
+    assert_eq!(cursor.next(), Ok(None));
+    source.drop("memory:customers").unwrap();
+    assert!(source.drop("memory:customers").is_err());
+  }
+}
@@ -0,0 +1,179 @@
+extern crate libc;
+
+use wiredtiger_def::{WT_EVENT_HANDLER, WT_SESSION, WT_CURSOR};
+
+use wiredtiger::string_from_ptr;
+
+use self::libc::{c_int, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// Error returned to Wired Tiger when a handler asks to stop an operation
+/// or panics.
+pub(crate) const WT_ERROR: c_int = -31802;
+
+/// Receives the errors, messages and progress reports Wired Tiger raises.
+/// Every method has a default implementation that forwards the event to the
+/// `log` crate, so implementations only override the events they handle.
+///
+/// A handler passed to `ConnectionOptions::event_handler` may be called from
+/// Wired Tiger's internal threads concurrently, as may the `Collator`,
+/// `Compressor`, `Extractor` and `DataSource` implementations registered
+/// with a connection. Panics in any of them are caught before they reach
+/// Wired Tiger and reported to it as errors.
+pub trait EventHandler {
+  /// Called when an error occurs, with the Wired Tiger error code and a
+  /// description of the failure.
+  fn on_error(&self, error: i32, message: &str) {
+    error!(target: "wiredtiger", "{} (error {})", message, error);
+  }
+
+  /// Called with informational messages.
+  fn on_message(&self, message: &str) {
+    info!(target: "wiredtiger", "{}", message);
+  }
+
+  /// Called periodically by long running operations such as compaction,
+  /// verification and salvage. Returning `false` asks Wired Tiger to stop
+  /// the operation.
+  fn on_progress(&self, operation: &str, progress: u64) -> bool {
+    debug!(target: "wiredtiger", "{}: {}", operation, progress);
+    true
+  }
+
+  /// Called when Wired Tiger closes a session or cursor the application has
+  /// not closed, usually because the connection is being closed.
+  fn on_close(&self) {
+    debug!(target: "wiredtiger", "handle closed by the connection");
+  }
+}
+
+/// An `EventHandler` that forwards every event to the `log` crate under the
+/// `wiredtiger` target instead of printing it to the standard error.
+/// # Examples
+/// ```
+/// let options = ConnectionOptions::new().event_handler(LogEventHandler);
+/// wiredtiger::open_with_options(&options);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogEventHandler;
+
+impl EventHandler for LogEventHandler {}
+
+impl<'a, T: EventHandler + ?Sized> EventHandler for &'a T {
+  fn on_error(&self, error: i32, message: &str) {
+    (**self).on_error(error, message)
+  }
+
+  fn on_message(&self, message: &str) {
+    (**self).on_message(message)
+  }
+
+  fn on_progress(&self, operation: &str, progress: u64) -> bool {
+    (**self).on_progress(operation, progress)
+  }
+
+  fn on_close(&self) {
+    (**self).on_close()
+  }
+}
+
+impl<T: EventHandler + ?Sized> EventHandler for Arc<T> {
+  fn on_error(&self, error: i32, message: &str) {
+    (**self).on_error(error, message)
+  }
+
+  fn on_message(&self, message: &str) {
+    (**self).on_message(message)
+  }
+
+  fn on_progress(&self, operation: &str, progress: u64) -> bool {
+    (**self).on_progress(operation, progress)
+  }
+
+  fn on_close(&self) {
+    (**self).on_close()
+  }
+}
+
+/// Exposes an `EventHandler` as a `WT_EVENT_HANDLER`.
+///
+/// Every bridge in the crate follows the same layout: it is `#[repr(C)]` and
+/// its first field is the Wired Tiger structure, so the pointer Wired Tiger
+/// passes back to a callback can be cast to the bridge.
+#[repr(C)]
+pub(crate) struct EventBridge<'a> {
+  wt_handler: WT_EVENT_HANDLER,
+  handler: Box<dyn EventHandler + 'a>
+}
+
+impl<'a> EventBridge<'a> {
+  /// Returns a boxed bridge so its address does not change while Wired Tiger
+  /// holds it.
+  pub(crate) fn new<H: EventHandler + 'a>(handler: H) -> Box<EventBridge<'a>> {
+    Box::new(EventBridge{
+      wt_handler: WT_EVENT_HANDLER{
+        handle_error: Some(handle_error),
+        handle_message: Some(handle_message),
+        handle_progress: Some(handle_progress),
+        handle_close: Some(handle_close)
+      },
+      handler: Box::new(handler)
+    })
+  }
+
+  /// Returns the pointer to pass to Wired Tiger.
+  pub(crate) fn as_ptr(&mut self) -> *mut WT_EVENT_HANDLER {
+    &mut self.wt_handler
+  }
+}
+
+/// Runs `f`, making sure a panic does not unwind into Wired Tiger.
+pub(crate) fn guard<F: FnOnce() -> c_int>(f: F) -> c_int {
+  match panic::catch_unwind(AssertUnwindSafe(f)) {
+    Ok(ret) => ret,
+    Err(_) => WT_ERROR
+  }
+}
+
+/// Runs `f` with the handler behind `handler`, making sure a panic does not
+/// unwind into Wired Tiger.
+unsafe fn with_handler<F>(handler: *mut WT_EVENT_HANDLER, f: F) -> c_int
+  where F: FnOnce(&dyn EventHandler) -> c_int {
+  let bridge = handler as *const EventBridge;
+  guard(|| f(&*(*bridge).handler))
+}
+
+extern "C" fn handle_error(handler: *mut WT_EVENT_HANDLER,
+  _session: *mut WT_SESSION, error: c_int, message: *const c_char) -> c_int {
+  unsafe {
+    let message = string_from_ptr(message);
+    with_handler(handler, |h| { h.on_error(error, &message); 0 })
+  }
+}
+
+extern "C" fn handle_message(handler: *mut WT_EVENT_HANDLER,
+  _session: *mut WT_SESSION, message: *const c_char) -> c_int {
+  unsafe {
+    let message = string_from_ptr(message);
+    with_handler(handler, |h| { h.on_message(&message); 0 })
+  }
+}
+
+extern "C" fn handle_progress(handler: *mut WT_EVENT_HANDLER,
+  _session: *mut WT_SESSION, operation: *const c_char, progress: u64)
+  -> c_int {
+  unsafe {
+    let operation = string_from_ptr(operation);
+    with_handler(handler, |h| {
+      if h.on_progress(&operation, progress) { 0 } else { WT_ERROR }
+    })
+  }
+}
+
+extern "C" fn handle_close(handler: *mut WT_EVENT_HANDLER,
+  _session: *mut WT_SESSION, _cursor: *mut WT_CURSOR) -> c_int {
+  unsafe {
+    with_handler(handler, |h| { h.on_close(); 0 })
+  }
+}
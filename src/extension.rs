@@ -0,0 +1,295 @@
+extern crate libc;
+
+use wiredtiger::{Connection, WT_NOTFOUND, c_str, get_error, report,
+  string_from_config_item, string_from_ptr};
+use wiredtiger_def::{WT_EXTENSION_API, WT_CONNECTION, WT_CONFIG_ARG,
+  WT_CONFIG_ITEM};
+use event_handler::{WT_ERROR, guard};
+
+use self::libc::{c_int, c_char, c_void};
+use std::ops::{Deref, DerefMut};
+use std::os::raw;
+use std::{ptr, slice};
+
+/// Prints the message passed to the `printf` style functions verbatim.
+const PRINT_FORMAT: &'static [u8] = b"%s\0";
+
+/// Wired Tiger's extension API, for code running inside Wired Tiger such as
+/// collators, compressors, extractors, data sources and extensions loaded
+/// from a shared library.
+///
+/// The handle is valid until the connection it was obtained from is closed,
+/// and may be used from any thread.
+#[derive(Clone, Copy)]
+pub struct ExtensionApi {
+  wt_api: *mut WT_EXTENSION_API
+}
+
+unsafe impl Send for ExtensionApi {}
+unsafe impl Sync for ExtensionApi {}
+
+/// The configuration Wired Tiger passes to an extension's initialization
+/// function, read with `ExtensionApi::config_get`.
+pub struct ExtensionConfig {
+  pub(crate) wt_config: *mut WT_CONFIG_ARG
+}
+
+/// Scratch memory allocated by Wired Tiger, freed when dropped.
+pub struct ScratchBuffer {
+  api: ExtensionApi,
+  data: *mut u8,
+  len: usize
+}
+
+impl Connection {
+  /// Returns the extension API of the connection.
+  /// # Examples
+  /// ```
+  /// let api = connection.extension_api().unwrap();
+  /// api.message("collator registered");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the extension API is not
+  /// available.
+  pub fn extension_api(&self) -> Result<ExtensionApi, String> {
+    unsafe {
+      match (*self.wt_con).get_extension_api {
+        Some(get_extension_api) => {
+          let wt_api = get_extension_api(self.wt_con);
+
+          if wt_api.is_null() {
+            return Err("Failed to get the extension API".to_string());
+          }
+
+          Ok(ExtensionApi{ wt_api: wt_api })
+        }
+        None => Err("Failed to get get_extension_api".to_string())
+      }
+    }
+  }
+}
+
+impl ExtensionApi {
+  /// Reports `message` as an error through the connection's event handler.
+  /// # Failures
+  /// The function returns `Err(message)` if the message failed to be sent.
+  pub fn error(&self, message: &str) -> Result<(), String> {
+    unsafe {
+      match (*self.wt_api).err_printf {
+        Some(err_printf) => {
+          let c_message = try!(c_str(message));
+          let ret = err_printf(self.wt_api, ptr::null_mut(),
+            PRINT_FORMAT.as_ptr() as *const c_char, c_message.as_ptr());
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get err_printf".to_string())
+      }
+    }
+  }
+
+  /// Reports `message` as an informational message through the
+  /// connection's event handler.
+  /// # Failures
+  /// The function returns `Err(message)` if the message failed to be sent.
+  pub fn message(&self, message: &str) -> Result<(), String> {
+    unsafe {
+      match (*self.wt_api).msg_printf {
+        Some(msg_printf) => {
+          let c_message = try!(c_str(message));
+          let ret = msg_printf(self.wt_api, ptr::null_mut(),
+            PRINT_FORMAT.as_ptr() as *const c_char, c_message.as_ptr());
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get msg_printf".to_string())
+      }
+    }
+  }
+
+  /// Returns the description of the Wired Tiger error `error`.
+  pub fn strerror(&self, error: i32) -> String {
+    unsafe {
+      match (*self.wt_api).strerror {
+        Some(strerror) => {
+          let message = strerror(self.wt_api, ptr::null_mut(), error);
+          if message.is_null() {
+            return format!("error {0}", error);
+          }
+          string_from_ptr(message)
+        }
+        None => get_error(error)
+      }
+    }
+  }
+
+  /// Returns the value of `key` in `config`, or `None` if it is not set.
+  /// Nested values are returned without their enclosing brackets.
+  /// # Examples
+  /// ```
+  /// let level = api.config_get(config, "level").unwrap();
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the configuration failed to be
+  /// read.
+  pub fn config_get(&self, config: &ExtensionConfig, key: &str)
+    -> Result<Option<String>, String> {
+    unsafe {
+      match (*self.wt_api).config_get {
+        Some(config_get) => {
+          let c_key = try!(c_str(key));
+          let mut item = WT_CONFIG_ITEM::default();
+          let ret = config_get(self.wt_api, ptr::null_mut(), config.wt_config,
+            c_key.as_ptr(), &mut item);
+
+          match ret {
+            0 => Ok(Some(string_from_config_item(&item))),
+            WT_NOTFOUND => Ok(None),
+            _ => Err(get_error(ret))
+          }
+        }
+        None => Err("Failed to get config_get".to_string())
+      }
+    }
+  }
+
+  /// Allocates `len` bytes of scratch memory from Wired Tiger.
+  /// # Failures
+  /// The function returns `Err(message)` if the memory could not be
+  /// allocated.
+  pub fn scratch(&self, len: usize) -> Result<ScratchBuffer, String> {
+    unsafe {
+      match (*self.wt_api).scr_alloc {
+        Some(scr_alloc) => {
+          let data = scr_alloc(self.wt_api, ptr::null_mut(), len as _);
+
+          if data.is_null() {
+            return Err(format!("Failed to allocate {0} bytes", len));
+          }
+
+          Ok(ScratchBuffer{
+            api: *self,
+            data: data as *mut u8,
+            len: len
+          })
+        }
+        None => Err("Failed to get scr_alloc".to_string())
+      }
+    }
+  }
+}
+
+impl Deref for ScratchBuffer {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    unsafe { slice::from_raw_parts(self.data, self.len) }
+  }
+}
+
+impl DerefMut for ScratchBuffer {
+  fn deref_mut(&mut self) -> &mut [u8] {
+    unsafe { slice::from_raw_parts_mut(self.data, self.len) }
+  }
+}
+
+impl Drop for ScratchBuffer {
+  fn drop(&mut self) {
+    unsafe {
+      if let Some(scr_free) = (*self.api.wt_api).scr_free {
+        scr_free(self.api.wt_api, ptr::null_mut(), self.data as *mut c_void);
+      }
+    }
+  }
+}
+
+/// Runs the initialization function of an extension built with
+/// `wiredtiger_extension!`. Not meant to be called directly.
+#[doc(hidden)]
+pub unsafe fn run_extension_init<F>(connection: *mut raw::c_void,
+  config: *mut raw::c_void, init: F) -> c_int
+  where F: FnOnce(&mut Connection, &ExtensionConfig) -> Result<(), String> {
+  guard(|| {
+    // The connection belongs to the application loading the extension.
+    let mut connection =
+      Connection::from_raw(connection as *mut WT_CONNECTION);
+    let config = ExtensionConfig{ wt_config: config as *mut WT_CONFIG_ARG };
+
+    match init(&mut connection, &config) {
+      Ok(()) => 0,
+      Err(message) => {
+        if let Ok(api) = connection.extension_api() {
+          let _ = api.error(&message);
+        }
+        WT_ERROR
+      }
+    }
+  })
+}
+
+/// Runs the termination function of an extension built with
+/// `wiredtiger_extension!`. Not meant to be called directly.
+#[doc(hidden)]
+pub unsafe fn run_extension_terminate<F>(connection: *mut raw::c_void,
+  terminate: F) -> c_int
+  where F: FnOnce(&mut Connection) -> Result<(), String> {
+  guard(|| {
+    let mut connection =
+      Connection::from_raw(connection as *mut WT_CONNECTION);
+
+    match terminate(&mut connection) {
+      Ok(()) => 0,
+      Err(message) => report("extension", message)
+    }
+  })
+}
+
+/// Exports `wiredtiger_extension_init`, and optionally
+/// `wiredtiger_extension_terminate`, from a crate built as a `cdylib` so
+/// Wired Tiger can load it with `Connection::load_extension`.
+///
+/// The initialization function receives the loading connection and the
+/// extension's configuration, and usually registers collators, compressors,
+/// extractors or data sources. The connection must not be closed.
+/// `examples/numeric_extension.rs` is built as such an extension.
+/// # Examples
+/// ```
+/// fn init(connection: &mut Connection, config: &ExtensionConfig)
+///   -> Result<(), String> {
+///   connection.add_collator("numeric", NumericCollator)
+/// }
+///
+/// wiredtiger_extension!(init);
+/// ```
+#[macro_export]
+macro_rules! wiredtiger_extension {
+  ($init:expr) => {
+    #[no_mangle]
+    pub extern "C" fn wiredtiger_extension_init(
+      connection: *mut ::std::os::raw::c_void,
+      config: *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int {
+      unsafe {
+        $crate::extension::run_extension_init(connection, config, $init)
+      }
+    }
+  };
+  ($init:expr, $terminate:expr) => {
+    $crate::wiredtiger_extension!($init);
+
+    #[no_mangle]
+    pub extern "C" fn wiredtiger_extension_terminate(
+      connection: *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int {
+      unsafe {
+        $crate::extension::run_extension_terminate(connection, $terminate)
+      }
+    }
+  };
+}
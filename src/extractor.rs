@@ -0,0 +1,246 @@
+extern crate libc;
+
+use wiredtiger::{Connection, c_str, get_error, report, slice_from_item,
+  string_from_config_item, string_from_ptr};
+use wiredtiger_def::{WT_EXTRACTOR, WT_SESSION, WT_ITEM, WT_CURSOR,
+  WT_CONFIG_ITEM};
+use event_handler::{WT_ERROR, guard};
+
+use self::libc::{c_int, c_char};
+use std::ffi::CString;
+use std::ptr;
+
+/// Produces the keys of the indexes created with `extractor=name`, where
+/// `name` is the name the extractor was registered with through
+/// `Connection::add_extractor`. The index must have string keys.
+pub trait Extractor: Send + Sync {
+  /// Returns the index keys for the record with the packed `key` and
+  /// `value`, so a string includes its trailing nul byte. A record can
+  /// produce no index keys or several of them.
+  fn extract(&self, key: &[u8], value: &[u8]) -> Result<Vec<String>, String>;
+
+  /// Returns an extractor to use for the index `uri` instead of this one,
+  /// based on the `app_metadata` the index was created with, or `None` to
+  /// keep using this extractor.
+  fn customize(&self, _uri: &str, _app_metadata: &str)
+    -> Result<Option<Box<dyn Extractor>>, String> {
+    Ok(None)
+  }
+}
+
+/// Exposes an `Extractor` as a `WT_EXTRACTOR`, laid out like `EventBridge`.
+#[repr(C)]
+struct ExtractorBridge {
+  wt_extractor: WT_EXTRACTOR,
+  extractor: Box<dyn Extractor>
+}
+
+impl ExtractorBridge {
+  fn new(extractor: Box<dyn Extractor>) -> *mut ExtractorBridge {
+    Box::into_raw(Box::new(ExtractorBridge{
+      wt_extractor: WT_EXTRACTOR{
+        extract: Some(extract),
+        customize: Some(customize),
+        terminate: Some(terminate)
+      },
+      extractor: extractor
+    }))
+  }
+}
+
+impl Connection {
+  /// Registers `extractor` under `name` so indexes can be created with
+  /// `IndexOptions::extractor(name)`. The extractor lives until the
+  /// connection is closed.
+  /// # Examples
+  /// ```
+  /// connection.add_extractor("tags", TagExtractor);
+  /// session.create_index("posts", "by_tag",
+  ///   &IndexOptions::new().extractor("tags").columns(&["tag"]));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the extractor failed to be
+  /// registered.
+  pub fn add_extractor<E>(&mut self, name: &str, extractor: E)
+    -> Result<(), String> where E: Extractor + 'static {
+    unsafe {
+      match (*self.wt_con).add_extractor {
+        Some(add_extractor) => {
+          let c_name = try!(c_str(name));
+          let bridge = ExtractorBridge::new(Box::new(extractor));
+          let ret = add_extractor(self.wt_con,
+            c_name.as_ptr(),
+            &mut (*bridge).wt_extractor,
+            ptr::null());
+
+          if ret != 0 {
+            drop(Box::from_raw(bridge));
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get add_extractor".to_string())
+      }
+    }
+  }
+}
+
+/// Returns the index keys `extractor` produces for the record with the
+/// packed `key` and `value`, ready to be set on the extraction cursor.
+fn index_keys(extractor: &dyn Extractor, key: &[u8], value: &[u8])
+  -> Result<Vec<CString>, String> {
+  let keys = try!(extractor.extract(key, value));
+  keys.into_iter()
+    .map(|key| CString::new(key)
+      .map_err(|_| "index key contains a nul byte".to_string()))
+    .collect()
+}
+
+/// Inserts every key in `keys` through the extraction cursor `cursor`.
+unsafe fn insert_keys(cursor: *mut WT_CURSOR, keys: Vec<CString>) -> c_int {
+  let (set_key, insert) = match ((*cursor).set_key, (*cursor).insert) {
+    (Some(set_key), Some(insert)) => (set_key, insert),
+    _ => return WT_ERROR
+  };
+
+  for key in keys {
+    set_key(cursor, key.as_ptr());
+    let ret = insert(cursor);
+    if ret != 0 {
+      return ret;
+    }
+  }
+
+  0
+}
+
+extern "C" fn extract(extractor: *mut WT_EXTRACTOR, _session: *mut WT_SESSION,
+  key: *const WT_ITEM, value: *const WT_ITEM, result_cursor: *mut WT_CURSOR)
+  -> c_int {
+  guard(|| unsafe {
+    let bridge = extractor as *const ExtractorBridge;
+    match index_keys(&*(*bridge).extractor, slice_from_item(key),
+      slice_from_item(value)) {
+      Ok(keys) => insert_keys(result_cursor, keys),
+      Err(message) => report("extractor", message)
+    }
+  })
+}
+
+extern "C" fn customize(extractor: *mut WT_EXTRACTOR,
+  _session: *mut WT_SESSION, uri: *const c_char, appcfg: *mut WT_CONFIG_ITEM,
+  customp: *mut *mut WT_EXTRACTOR) -> c_int {
+  guard(|| unsafe {
+    let bridge = extractor as *const ExtractorBridge;
+    let uri = string_from_ptr(uri);
+    let app_metadata = string_from_config_item(appcfg);
+
+    match (*bridge).extractor.customize(&uri, &app_metadata) {
+      Ok(Some(custom)) => {
+        *customp = &mut (*ExtractorBridge::new(custom)).wt_extractor;
+        0
+      }
+      Ok(None) => 0,
+      Err(message) => report("extractor", message)
+    }
+  })
+}
+
+extern "C" fn terminate(extractor: *mut WT_EXTRACTOR,
+  _session: *mut WT_SESSION) -> c_int {
+  guard(|| unsafe {
+    drop(Box::from_raw(extractor as *mut ExtractorBridge));
+    0
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Extractor, ExtractorBridge, customize, extract, index_keys,
+    terminate};
+  use event_handler::WT_ERROR;
+  use wiredtiger::item_from_bytes;
+  use wiredtiger_def::{WT_CONFIG_ITEM, WT_EXTRACTOR};
+
+  use std::ffi::CString;
+  use std::ptr;
+
+  /// Indexes a record under each comma separated tag of its value.
+  struct TagExtractor {
+    prefix: String
+  }
+
+  impl Extractor for TagExtractor {
+    fn extract(&self, _key: &[u8], value: &[u8])
+      -> Result<Vec<String>, String> {
+      let value = String::from_utf8_lossy(value);
+      Ok(value.trim_end_matches('\0').split(',')
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| format!("{0}{1}", self.prefix, tag))
+        .collect())
+    }
+
+    fn customize(&self, _uri: &str, app_metadata: &str)
+      -> Result<Option<Box<dyn Extractor>>, String> {
+      if app_metadata.is_empty() {
+        return Ok(None);
+      }
+      Ok(Some(Box::new(TagExtractor{ prefix: app_metadata.to_string() })))
+    }
+  }
+
+  fn tags() -> TagExtractor {
+    TagExtractor{ prefix: String::new() }
+  }
+
+  fn keys(keys: &[&str]) -> Vec<CString> {
+    keys.iter().map(|k| CString::new(*k).unwrap()).collect()
+  }
+
+  #[test]
+  fn splits_records_into_index_keys() {
+    assert_eq!(index_keys(&tags(), b"post1\0", b"rust,wiredtiger\0"),
+      Ok(keys(&["rust", "wiredtiger"])));
+    assert_eq!(index_keys(&tags(), b"post2\0", b"\0"), Ok(Vec::new()));
+    assert!(index_keys(&tags(), b"post3\0", b"a\0b\0").is_err());
+  }
+
+  #[test]
+  fn extract_reports_keys_with_nul_bytes() {
+    let bridge = ExtractorBridge::new(Box::new(tags()));
+    let key = item_from_bytes(b"post3\0");
+    let value = item_from_bytes(b"a\0b\0");
+    // The keys are rejected before the cursor is used.
+    assert_eq!(extract(bridge as *mut WT_EXTRACTOR, ptr::null_mut(), &key,
+      &value, ptr::null_mut()), WT_ERROR);
+    assert_eq!(terminate(bridge as *mut WT_EXTRACTOR, ptr::null_mut()), 0);
+  }
+
+  #[test]
+  fn customize_returns_an_extractor_for_the_index() {
+    let bridge = ExtractorBridge::new(Box::new(tags()));
+    let uri = CString::new("index:posts:by_tag").unwrap();
+    let metadata = "tag:";
+    let mut app_metadata = WT_CONFIG_ITEM::default();
+    app_metadata._str = metadata.as_ptr() as *const _;
+    app_metadata.len = metadata.len() as _;
+    let mut custom: *mut WT_EXTRACTOR = ptr::null_mut();
+
+    assert_eq!(customize(bridge as *mut WT_EXTRACTOR, ptr::null_mut(),
+      uri.as_ptr(), &mut app_metadata, &mut custom), 0);
+    assert!(!custom.is_null());
+    let custom_bridge = custom as *mut ExtractorBridge;
+    assert_eq!(index_keys(unsafe { &*(*custom_bridge).extractor }, b"\0",
+      b"rust\0"), Ok(keys(&["tag:rust"])));
+
+    let mut empty = WT_CONFIG_ITEM::default();
+    let mut unchanged: *mut WT_EXTRACTOR = ptr::null_mut();
+    assert_eq!(customize(bridge as *mut WT_EXTRACTOR, ptr::null_mut(),
+      uri.as_ptr(), &mut empty, &mut unchanged), 0);
+    assert!(unchanged.is_null());
+
+    assert_eq!(terminate(custom, ptr::null_mut()), 0);
+    assert_eq!(terminate(bridge as *mut WT_EXTRACTOR, ptr::null_mut()), 0);
+  }
+}
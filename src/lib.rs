@@ -4,9 +4,26 @@
 #![crate_type = "lib"]
 
 extern crate libc;
+#[macro_use]
+extern crate log;
 
 mod wiredtiger_def;
 
 /// A friendly Rust wrapper for the Wired Tiger C library
 
-pub mod wiredtiger;
\ No newline at end of file
+pub mod wiredtiger;
+pub mod event_handler;
+pub mod backup;
+pub mod schema;
+pub mod log_reader;
+pub mod replication;
+pub mod maintenance;
+pub mod collator;
+pub mod compressor;
+pub mod extractor;
+pub mod data_source;
+pub mod async_op;
+pub mod extension;
+pub mod statistics;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
@@ -0,0 +1,268 @@
+extern crate libc;
+
+use wiredtiger::{Session, Cursor, CursorOptions, WT_NOTFOUND, get_error,
+  slice_from_item};
+use wiredtiger_def::WT_ITEM;
+
+use self::libc::c_uint;
+
+// The record types and operations defined in wiredtiger.h.
+const WT_LOGREC_CHECKPOINT: u32 = 0;
+const WT_LOGREC_COMMIT: u32 = 1;
+const WT_LOGREC_FILE_SYNC: u32 = 2;
+const WT_LOGREC_MESSAGE: u32 = 3;
+const WT_LOGOP_COL_PUT: u32 = 1;
+const WT_LOGOP_COL_REMOVE: u32 = 2;
+const WT_LOGOP_COL_TRUNCATE: u32 = 3;
+const WT_LOGOP_ROW_PUT: u32 = 4;
+const WT_LOGOP_ROW_REMOVE: u32 = 5;
+const WT_LOGOP_ROW_TRUNCATE: u32 = 6;
+
+/// A position in the database log.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Lsn {
+  pub file: u32,
+  pub offset: u32
+}
+
+/// The type of a log record.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogRecordType {
+  Checkpoint,
+  Commit,
+  FileSync,
+  Message,
+  Other(u32)
+}
+
+/// The operation performed by a log record that is part of a commit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogOperation {
+  ColumnPut,
+  ColumnRemove,
+  ColumnTruncate,
+  RowPut,
+  RowRemove,
+  RowTruncate,
+  Other(u32)
+}
+
+/// A record read from the database log.
+/// `key` and `value` hold the packed key and value of the operation and are
+/// empty for records that are not operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogRecord {
+  pub lsn: Lsn,
+  pub op_count: u32,
+  pub txn_id: u64,
+  pub record_type: LogRecordType,
+  pub operation: LogOperation,
+  pub file_id: u32,
+  pub key: Vec<u8>,
+  pub value: Vec<u8>
+}
+
+/// Reads the records written to the database log through a `log:` cursor.
+/// The connection must be opened with `ConnectionOptions::log`, and with
+/// `Logging::archive` set to `false` to read records older than the last
+/// checkpoint.
+/// # Examples
+/// ```
+/// let options = ConnectionOptions::new()
+///   .home("/var/lib/users")
+///   .log(Logging::new().archive(false));
+/// let mut connection = wiredtiger::open_with_options(&options).unwrap();
+/// let reader = connection.open_session().unwrap().open_log_reader();
+/// ```
+pub struct LogReader {
+  cursor: Cursor
+}
+
+impl LogRecordType {
+  pub(crate) fn from_raw(value: u32) -> LogRecordType {
+    match value {
+      WT_LOGREC_CHECKPOINT => LogRecordType::Checkpoint,
+      WT_LOGREC_COMMIT => LogRecordType::Commit,
+      WT_LOGREC_FILE_SYNC => LogRecordType::FileSync,
+      WT_LOGREC_MESSAGE => LogRecordType::Message,
+      other => LogRecordType::Other(other)
+    }
+  }
+}
+
+impl LogOperation {
+  pub(crate) fn from_raw(value: u32) -> LogOperation {
+    match value {
+      WT_LOGOP_COL_PUT => LogOperation::ColumnPut,
+      WT_LOGOP_COL_REMOVE => LogOperation::ColumnRemove,
+      WT_LOGOP_COL_TRUNCATE => LogOperation::ColumnTruncate,
+      WT_LOGOP_ROW_PUT => LogOperation::RowPut,
+      WT_LOGOP_ROW_REMOVE => LogOperation::RowRemove,
+      WT_LOGOP_ROW_TRUNCATE => LogOperation::RowTruncate,
+      other => LogOperation::Other(other)
+    }
+  }
+}
+
+impl Session {
+  /// Opens a `LogReader` positioned before the first record in the log.
+  /// # Examples
+  /// ```
+  /// for record in session.open_log_reader().unwrap() {
+  ///   // handle record
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the `log:` cursor could not be
+  /// opened, such as when the connection was opened without
+  /// `ConnectionOptions::log`.
+  pub fn open_log_reader(&mut self) -> Result<LogReader, String> {
+    let cursor = try!(self.open_uri_cursor("log:", &CursorOptions::new()));
+    Ok(LogReader{
+      cursor: cursor
+    })
+  }
+
+  /// Opens a `LogReader` that resumes reading after the record at `lsn`,
+  /// usually the last record processed before a restart.
+  /// # Examples
+  /// ```
+  /// let reader = session.open_log_reader_after(saved_lsn);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the `log:` cursor could not be
+  /// opened or `lsn` is no longer in the log.
+  pub fn open_log_reader_after(&mut self, lsn: Lsn)
+    -> Result<LogReader, String> {
+    let mut reader = try!(self.open_log_reader());
+    try!(reader.seek(lsn));
+    Ok(reader)
+  }
+}
+
+impl LogReader {
+  /// Moves to the next record in the log and returns it, or `None` once the
+  /// end of the log is reached. Records written afterwards can be read by
+  /// calling `next_record` again.
+  /// # Failures
+  /// The function returns `Err(message)` if the record could not be read.
+  pub fn next_record(&mut self) -> Result<Option<LogRecord>, String> {
+    let wt_cursor = self.cursor.wt_cursor;
+    unsafe {
+      let next = match (*wt_cursor).next {
+        Some(next) => next,
+        None => return Err("Failed to get next".to_string())
+      };
+      let ret = next(wt_cursor);
+
+      if ret == WT_NOTFOUND {
+        return Ok(None);
+      }
+      if ret != 0 {
+        return Err(get_error(ret));
+      }
+
+      self.read_record().map(Some)
+    }
+  }
+
+  fn seek(&mut self, lsn: Lsn) -> Result<(), String> {
+    let wt_cursor = self.cursor.wt_cursor;
+    unsafe {
+      match ((*wt_cursor).set_key, (*wt_cursor).search) {
+        (Some(set_key), Some(search)) => {
+          set_key(wt_cursor, lsn.file as c_uint, lsn.offset as c_uint,
+            0 as c_uint);
+          let ret = search(wt_cursor);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        _ => Err("Failed to get search".to_string())
+      }
+    }
+  }
+
+  unsafe fn read_record(&mut self) -> Result<LogRecord, String> {
+    let wt_cursor = self.cursor.wt_cursor;
+    let (get_key, get_value) = match ((*wt_cursor).get_key,
+      (*wt_cursor).get_value) {
+      (Some(get_key), Some(get_value)) => (get_key, get_value),
+      _ => return Err("Failed to get get_key".to_string())
+    };
+
+    let mut file: c_uint = 0;
+    let mut offset: c_uint = 0;
+    let mut op_count: c_uint = 0;
+    let ret = get_key(wt_cursor, &mut file, &mut offset, &mut op_count);
+    if ret != 0 {
+      return Err(get_error(ret));
+    }
+
+    let mut txn_id: u64 = 0;
+    let mut record_type: c_uint = 0;
+    let mut operation: c_uint = 0;
+    let mut file_id: c_uint = 0;
+    let mut key = WT_ITEM::default();
+    let mut value = WT_ITEM::default();
+    let ret = get_value(wt_cursor, &mut txn_id, &mut record_type,
+      &mut operation, &mut file_id, &mut key, &mut value);
+    if ret != 0 {
+      return Err(get_error(ret));
+    }
+
+    Ok(LogRecord{
+      lsn: Lsn{
+        file: file,
+        offset: offset
+      },
+      op_count: op_count,
+      txn_id: txn_id,
+      record_type: LogRecordType::from_raw(record_type),
+      operation: LogOperation::from_raw(operation),
+      file_id: file_id,
+      key: slice_from_item(&key).to_vec(),
+      value: slice_from_item(&value).to_vec()
+    })
+  }
+}
+
+impl Iterator for LogReader {
+  type Item = LogRecord;
+  /// Returns the next record, stopping at the end of the log or on the
+  /// first error. Use `next_record` to tell both cases apart.
+  fn next(&mut self) -> Option<LogRecord> {
+    match self.next_record() {
+      Ok(record) => record,
+      Err(_) => None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{LogOperation, LogRecordType};
+
+  #[test]
+  fn decodes_the_record_types_of_wiredtiger_h() {
+    assert_eq!(LogRecordType::from_raw(0), LogRecordType::Checkpoint);
+    assert_eq!(LogRecordType::from_raw(1), LogRecordType::Commit);
+    assert_eq!(LogRecordType::from_raw(2), LogRecordType::FileSync);
+    assert_eq!(LogRecordType::from_raw(3), LogRecordType::Message);
+    assert_eq!(LogRecordType::from_raw(4), LogRecordType::Other(4));
+  }
+
+  #[test]
+  fn decodes_the_operations_of_wiredtiger_h() {
+    assert_eq!(LogOperation::from_raw(0), LogOperation::Other(0));
+    assert_eq!(LogOperation::from_raw(1), LogOperation::ColumnPut);
+    assert_eq!(LogOperation::from_raw(2), LogOperation::ColumnRemove);
+    assert_eq!(LogOperation::from_raw(3), LogOperation::ColumnTruncate);
+    assert_eq!(LogOperation::from_raw(4), LogOperation::RowPut);
+    assert_eq!(LogOperation::from_raw(5), LogOperation::RowRemove);
+    assert_eq!(LogOperation::from_raw(6), LogOperation::RowTruncate);
+  }
+}
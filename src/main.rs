@@ -1,4 +1,8 @@
+#[macro_use]
+extern crate log;
+
 mod wiredtiger_def;
+mod event_handler;
 mod wiredtiger;
 
 fn main() {
@@ -0,0 +1,354 @@
+use wiredtiger::{Connection, Session, CursorOptions};
+use event_handler::{EventHandler, EventBridge};
+
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// A progress report raised by a long running operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Progress {
+  pub operation: String,
+  pub counter: u64
+}
+
+/// Cancels a running operation from another thread.
+/// Clones share the same state, so a clone can be handed to the thread that
+/// decides to cancel. Each operation taking a `Cancellation` documents when
+/// it checks it.
+/// # Examples
+/// ```
+/// let cancellation = Cancellation::new();
+/// let handle = cancellation.clone();
+/// thread::spawn(move || {
+///   thread::sleep(Duration::from_secs(60));
+///   handle.cancel();
+/// });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+  cancelled: Arc<AtomicBool>
+}
+
+impl Cancellation {
+  pub fn new() -> Cancellation {
+    Cancellation::default()
+  }
+
+  /// Asks the operation to stop the next time it reports progress.
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::SeqCst);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::SeqCst)
+  }
+}
+
+/// An error reported while verifying an object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifyError {
+  pub code: i32,
+  pub message: String
+}
+
+/// The outcome of verifying an object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifyReport {
+  /// The errors reported while verifying, empty when the object is sound.
+  pub errors: Vec<VerifyError>,
+  /// The informational messages reported while verifying, including the
+  /// output of the `dump_*` options.
+  pub messages: Vec<String>,
+  /// The result of the verify call, `Err(message)` when the object is
+  /// corrupted or could not be verified, such as when it does not exist or
+  /// is open.
+  pub result: Result<(), String>
+}
+
+impl VerifyReport {
+  /// Returns `true` when the verification succeeded without reporting
+  /// errors.
+  pub fn is_ok(&self) -> bool {
+    self.result.is_ok() && self.errors.is_empty()
+  }
+}
+
+impl Default for VerifyReport {
+  fn default() -> VerifyReport {
+    VerifyReport{
+      errors: Vec::new(),
+      messages: Vec::new(),
+      result: Ok(())
+    }
+  }
+}
+
+/// Options used to configure `Session::verify`.
+/// The `dump_*` options require a Wired Tiger library built with diagnostics
+/// enabled.
+#[derive(Clone, Debug, Default)]
+pub struct VerifyOptions {
+  dump_address: bool,
+  dump_blocks: bool,
+  dump_pages: bool,
+  dump_shape: bool
+}
+
+impl VerifyOptions {
+  pub fn new() -> VerifyOptions {
+    VerifyOptions::default()
+  }
+
+  /// Reports the addresses of the pages as they are verified.
+  pub fn dump_address(mut self, dump_address: bool) -> VerifyOptions {
+    self.dump_address = dump_address;
+    self
+  }
+
+  /// Reports the blocks of the file as they are verified.
+  pub fn dump_blocks(mut self, dump_blocks: bool) -> VerifyOptions {
+    self.dump_blocks = dump_blocks;
+    self
+  }
+
+  /// Reports the contents of the pages as they are verified.
+  pub fn dump_pages(mut self, dump_pages: bool) -> VerifyOptions {
+    self.dump_pages = dump_pages;
+    self
+  }
+
+  /// Reports the shape of the tree once verified.
+  pub fn dump_shape(mut self, dump_shape: bool) -> VerifyOptions {
+    self.dump_shape = dump_shape;
+    self
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = Vec::new();
+
+    if self.dump_address {
+      config.push("dump_address=true");
+    }
+    if self.dump_blocks {
+      config.push("dump_blocks=true");
+    }
+    if self.dump_pages {
+      config.push("dump_pages=true");
+    }
+    if self.dump_shape {
+      config.push("dump_shape=true");
+    }
+
+    config.join(",")
+  }
+}
+
+/// The outcome of upgrading a single object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpgradeResult {
+  pub uri: String,
+  pub result: Result<(), String>
+}
+
+/// Collects the events raised while running a maintenance operation.
+struct OperationHandler<F: FnMut(&Progress)> {
+  on_progress: RefCell<F>,
+  report: RefCell<VerifyReport>
+}
+
+impl<F: FnMut(&Progress)> OperationHandler<F> {
+  fn new(on_progress: F) -> OperationHandler<F> {
+    OperationHandler{
+      on_progress: RefCell::new(on_progress),
+      report: RefCell::new(VerifyReport::default())
+    }
+  }
+}
+
+impl<F: FnMut(&Progress)> EventHandler for OperationHandler<F> {
+  fn on_error(&self, error: i32, message: &str) {
+    self.report.borrow_mut().errors.push(VerifyError{
+      code: error,
+      message: message.to_string()
+    });
+  }
+
+  fn on_message(&self, message: &str) {
+    self.report.borrow_mut().messages.push(message.to_string());
+  }
+
+  fn on_progress(&self, operation: &str, counter: u64) -> bool {
+    (&mut *self.on_progress.borrow_mut())(&Progress{
+      operation: operation.to_string(),
+      counter: counter
+    });
+    true
+  }
+}
+
+/// Rounds a non-zero `timeout` up to whole seconds, since a zero timeout
+/// disables the limit.
+fn compact_config(timeout: Option<Duration>) -> String {
+  match timeout {
+    Some(timeout) => {
+      let mut secs = timeout.as_secs();
+      if timeout.subsec_nanos() > 0 {
+        secs += 1;
+      }
+      format!("timeout={0}", secs)
+    }
+    None => String::new()
+  }
+}
+
+impl Session {
+  /// Rewrites the table `name` to return unused space to the file system.
+  /// The operation fails if it does not complete within `timeout`, which
+  /// defaults to Wired Tiger's limit of 20 minutes when `None` and is
+  /// otherwise rounded up to whole seconds. A zero `timeout` disables the
+  /// limit.
+  /// # Examples
+  /// ```
+  /// session.compact("users", Some(Duration::from_secs(3600)));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table failed to be
+  /// compacted.
+  pub fn compact(&mut self, name: &str, timeout: Option<Duration>)
+    -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).compact };
+    self.call_with_uri(method, "compact", &format!("table:{0}", name),
+      &compact_config(timeout))
+  }
+
+  /// Verifies the integrity of the object `uri`, such as `table:users`, and
+  /// returns the result of the verification along with the errors Wired
+  /// Tiger reported while checking it.
+  /// The object must not be open in any other session.
+  /// # Examples
+  /// ```
+  /// let report = try!(session.verify("table:users", &VerifyOptions::new()));
+  /// if let Err(message) = report.result {
+  ///   println!("verify failed: {}", message);
+  /// }
+  /// for error in report.errors {
+  ///   println!("{}: {}", error.code, error.message);
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the verification could not be
+  /// started. A failed verification is returned in the report.
+  pub fn verify(&mut self, uri: &str, options: &VerifyOptions)
+    -> Result<VerifyReport, String> {
+    let handler = OperationHandler::new(|_: &Progress| ());
+    let result = try!(self.run_with_handler(&handler, |session| {
+      let method = unsafe { (*session.wt_session).verify };
+      Ok(session.call_with_uri(method, "verify", uri, &options.to_config()))
+    }));
+
+    let mut report = handler.report.into_inner();
+    report.result = result;
+    Ok(report)
+  }
+
+  /// Rebuilds the object `uri`, such as `table:users`, keeping every record
+  /// that can still be read, and calls `on_progress` each time Wired Tiger
+  /// reports progress. The object must not be open in any other session.
+  /// # Examples
+  /// ```
+  /// session.salvage("table:users", |p| println!("{}", p.counter));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the object could not be
+  /// salvaged.
+  pub fn salvage<F>(&mut self, uri: &str, on_progress: F)
+    -> Result<(), String> where F: FnMut(&Progress) {
+    let handler = OperationHandler::new(on_progress);
+    self.run_with_handler(&handler, |session| {
+      let method = unsafe { (*session.wt_session).salvage };
+      session.call_with_uri(method, "salvage", uri, "")
+    })
+  }
+
+  /// Runs `operation` on a new session whose events are sent to `handler`.
+  fn run_with_handler<T, H, O>(&mut self, handler: &H, operation: O)
+    -> Result<T, String>
+    where H: EventHandler, O: FnOnce(&mut Session) -> Result<T, String> {
+    let mut bridge = EventBridge::new(handler);
+    let mut session = try!(self.open_sibling(bridge.as_ptr()));
+    operation(&mut session)
+  }
+}
+
+impl Connection {
+  /// Upgrades every table, LSM tree and file listed in the database metadata
+  /// and returns the outcome for each of them. A failure to upgrade one
+  /// object does not stop the others from being upgraded.
+  /// # Examples
+  /// ```
+  /// for upgrade in try!(connection.upgrade_all()) {
+  ///   if let Err(message) = upgrade.result {
+  ///     println!("{} failed: {}", upgrade.uri, message);
+  ///   }
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the metadata could not be read.
+  pub fn upgrade_all(&mut self) -> Result<Vec<UpgradeResult>, String> {
+    let mut session = try!(self.open_session());
+    let uris: Vec<String> = {
+      let metadata = try!(session.open_uri_cursor("metadata:",
+        &CursorOptions::new()));
+      metadata
+        .map(|kvp| kvp.key)
+        .filter(|key| key.starts_with("table:") || key.starts_with("lsm:") ||
+          key.starts_with("file:"))
+        .collect()
+    };
+
+    Ok(uris.into_iter().map(|uri| {
+      let result = session.upgrade(&uri);
+      UpgradeResult{
+        uri: uri,
+        result: result
+      }
+    }).collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{VerifyError, VerifyReport, compact_config};
+  use std::time::Duration;
+
+  #[test]
+  fn verify_reports_are_ok_only_without_errors_or_failure() {
+    assert!(VerifyReport::default().is_ok());
+    let failed = VerifyReport{
+      result: Err("Device or resource busy".to_string()),
+      ..VerifyReport::default()
+    };
+    assert!(!failed.is_ok());
+    let corrupted = VerifyReport{
+      errors: vec![VerifyError{
+        code: -31802,
+        message: "checksum mismatch".to_string()
+      }],
+      ..VerifyReport::default()
+    };
+    assert!(!corrupted.is_ok());
+  }
+
+  #[test]
+  fn compact_timeouts_are_rounded_up_to_seconds() {
+    assert_eq!(compact_config(None), "");
+    assert_eq!(compact_config(Some(Duration::from_secs(0))), "timeout=0");
+    assert_eq!(compact_config(Some(Duration::from_millis(500))), "timeout=1");
+    assert_eq!(compact_config(Some(Duration::from_millis(1500))),
+      "timeout=2");
+    assert_eq!(compact_config(Some(Duration::from_secs(3600))),
+      "timeout=3600");
+  }
+}
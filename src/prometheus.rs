@@ -0,0 +1,216 @@
+use wiredtiger::Session;
+use statistics::Statistics;
+use maintenance::Cancellation;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// How long `serve` waits on a client before dropping its request.
+const CLIENT_TIMEOUT_SECS: u64 = 10;
+
+/// Renders the statistics of a connection and its tables in the Prometheus
+/// text exposition format.
+///
+/// A statistic becomes a metric named after its description, so
+/// `cache: bytes currently in the cache` is exported as
+/// `wiredtiger_cache_bytes_currently_in_the_cache`, and table statistics as
+/// `wiredtiger_table_...` metrics labelled with the table name.
+/// # Examples
+/// ```
+/// let exporter = PrometheusExporter::new().table("users");
+/// let listener = TcpListener::bind("127.0.0.1:9187").unwrap();
+/// exporter.serve(&mut session, &listener);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PrometheusExporter {
+  tables: Vec<String>
+}
+
+/// The samples of a metric and the description it was named after.
+type Metric = (String, Vec<(Option<String>, i64)>);
+
+impl PrometheusExporter {
+  /// Returns an exporter for the statistics of every table.
+  pub fn new() -> PrometheusExporter {
+    PrometheusExporter::default()
+  }
+
+  /// Exports the statistics of `table_name` only, along with the other
+  /// tables added this way.
+  pub fn table(mut self, table_name: &str) -> PrometheusExporter {
+    self.tables.push(table_name.to_string());
+    self
+  }
+
+  /// Returns the current statistics in the Prometheus text format.
+  /// # Failures
+  /// The function returns `Err(message)` if the statistics could not be read.
+  pub fn render(&self, session: &mut Session) -> Result<String, String> {
+    let mut metrics: BTreeMap<String, Metric> = BTreeMap::new();
+    add_metrics(&mut metrics, "wiredtiger_", None, &try!(session.stats()));
+
+    let tables = if self.tables.is_empty() {
+      try!(session.list_tables())
+    } else {
+      self.tables.clone()
+    };
+    for table in tables {
+      let statistics = try!(session.table_stats(&table));
+      add_metrics(&mut metrics, "wiredtiger_table_", Some(&table), &statistics);
+    }
+
+    let mut text = String::new();
+    for (name, (description, samples)) in metrics {
+      text.push_str(&format!("# HELP {0} {1}\n", name, description));
+      text.push_str(&format!("# TYPE {0} untyped\n", name));
+      for (table, value) in samples {
+        match table {
+          Some(table) => text.push_str(&format!("{0}{{table=\"{1}\"}} {2}\n",
+            name, escape_label(&table), value)),
+          None => text.push_str(&format!("{0} {1}\n", name, value))
+        }
+      }
+    }
+
+    Ok(text)
+  }
+
+  /// Writes the current statistics to `path`, replacing the file at once so
+  /// a collector never reads a partial file.
+  /// # Failures
+  /// The function returns `Err(message)` if the statistics could not be read
+  /// or the file could not be written.
+  pub fn write_to(&self, session: &mut Session, path: &Path)
+    -> Result<(), String> {
+    let text = try!(self.render(session));
+    let partial = path.with_extension("prom.tmp");
+    try!(fs::write(&partial, text).map_err(|e| e.to_string()));
+    fs::rename(&partial, path).map_err(|e| e.to_string())
+  }
+
+  /// Writes the statistics to `path` every `interval` until `cancellation`
+  /// is cancelled.
+  /// # Examples
+  /// ```
+  /// let path = Path::new("/var/lib/node_exporter/wiredtiger.prom");
+  /// exporter.write_every(&mut session, path, Duration::from_secs(15),
+  ///   &Cancellation::new());
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the statistics could not be read
+  /// or the file could not be written.
+  pub fn write_every(&self, session: &mut Session, path: &Path,
+    interval: Duration, cancellation: &Cancellation) -> Result<(), String> {
+    while !cancellation.is_cancelled() {
+      try!(self.write_to(session, path));
+      thread::sleep(interval);
+    }
+    Ok(())
+  }
+
+  /// Answers the HTTP requests accepted by `listener` with the current
+  /// statistics, one request at a time, or with a 500 error if they could
+  /// not be read. Clients idle for 10 seconds are dropped so they cannot
+  /// stall the others. The function only returns if the listener fails.
+  /// # Failures
+  /// The function returns `Err(message)` if the listener fails.
+  pub fn serve(&self, session: &mut Session, listener: &TcpListener)
+    -> Result<(), String> {
+    for stream in listener.incoming() {
+      let mut stream = try!(stream.map_err(|e| e.to_string()));
+      let timeout = Some(Duration::from_secs(CLIENT_TIMEOUT_SECS));
+      if stream.set_read_timeout(timeout).is_err() ||
+        stream.set_write_timeout(timeout).is_err() {
+        continue;
+      }
+
+      let mut request_line = String::new();
+      {
+        let mut reader = BufReader::new(&stream);
+        if reader.read_line(&mut request_line).is_err() {
+          continue;
+        }
+        // Drain the headers up to the blank line ending them; the request
+        // has no body.
+        let mut header = String::new();
+        loop {
+          header.clear();
+          match reader.read_line(&mut header) {
+            Ok(n) if n > 0 && !header.trim_end().is_empty() => (),
+            _ => break
+          }
+        }
+      }
+
+      let response = if request_line.starts_with("GET /metrics ") ||
+        request_line.starts_with("GET / ") {
+        match self.render(session) {
+          Ok(body) => format!("HTTP/1.0 200 OK\r\n\
+            Content-Type: text/plain; version=0.0.4\r\n\
+            Content-Length: {0}\r\n\r\n{1}", body.len(), body),
+          Err(message) => format!("HTTP/1.0 500 Internal Server Error\r\n\
+            Content-Type: text/plain\r\n\
+            Content-Length: {0}\r\n\r\n{1}", message.len(), message)
+        }
+      } else {
+        "HTTP/1.0 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string()
+      };
+      // A client going away is not a reason to stop serving.
+      let _ = stream.write_all(response.as_bytes());
+    }
+
+    Ok(())
+  }
+}
+
+fn add_metrics(metrics: &mut BTreeMap<String, Metric>, prefix: &str,
+  table: Option<&str>, statistics: &Statistics) {
+  for (description, value) in statistics.iter() {
+    let name = format!("{0}{1}", prefix, metric_name(description));
+    let metric = metrics.entry(name)
+      .or_insert_with(|| (description.clone(), Vec::new()));
+    metric.1.push((table.map(|t| t.to_string()), *value));
+  }
+}
+
+/// Turns a statistic description into the part of a metric name after the
+/// prefix, keeping letters and digits and joining the words with `_`.
+fn metric_name(description: &str) -> String {
+  let mut name = String::new();
+  for c in description.chars() {
+    if c.is_ascii_alphanumeric() {
+      name.push(c.to_ascii_lowercase());
+    } else if !name.is_empty() && !name.ends_with('_') {
+      name.push('_');
+    }
+  }
+  name.trim_end_matches('_').to_string()
+}
+
+fn escape_label(value: &str) -> String {
+  value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{escape_label, metric_name};
+
+  #[test]
+  fn metric_names_follow_descriptions() {
+    assert_eq!(metric_name("cache: bytes currently in the cache"),
+      "cache_bytes_currently_in_the_cache");
+    assert_eq!(metric_name("LSM: tree maintenance operations (ops)"),
+      "lsm_tree_maintenance_operations_ops");
+    assert_eq!(metric_name("  -- "), "");
+  }
+
+  #[test]
+  fn escapes_label_values() {
+    assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+  }
+}
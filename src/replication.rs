@@ -0,0 +1,549 @@
+use wiredtiger;
+use wiredtiger::{Connection, ConnectionOptions, Session, Cursor,
+  CursorOptions};
+use log_reader::{Lsn, LogReader, LogRecord, LogRecordType, LogOperation};
+
+use std::collections::HashMap;
+use std::io::{Read, Write, ErrorKind};
+
+/// The table where a follower records the last log position it applied.
+const STATE_TABLE: &'static str = "replication_state";
+const LAST_APPLIED_KEY: &'static str = "last_applied";
+
+const OP_PUT: u8 = 1;
+const OP_REMOVE: u8 = 2;
+
+/// A change to a single record shipped from the primary to a follower.
+#[derive(Clone, Debug, PartialEq)]
+enum Change {
+  Put { uri: String, key: Vec<u8>, value: Vec<u8> },
+  Remove { uri: String, key: Vec<u8> }
+}
+
+/// The changes of a committed transaction, identified by the log position of
+/// its commit record.
+#[derive(Clone, Debug, PartialEq)]
+struct Batch {
+  lsn: Lsn,
+  changes: Vec<Change>
+}
+
+/// Streams the transactions committed on a database to a follower.
+/// Only row and column store puts and removes are shipped; schema changes
+/// and truncates must be applied to the follower separately.
+pub struct Primary {
+  session: Session,
+  last_shipped: Option<Lsn>,
+  /// The `file:` URIs of the log file ids seen, `None` for files that are
+  /// not replicated or no longer exist.
+  files: HashMap<u32, Option<String>>
+}
+
+/// Applies the transactions shipped by a `Primary` to its own database.
+/// The follower database must already contain the replicated tables.
+pub struct Follower {
+  state: Cursor,
+  cursors: HashMap<String, Cursor>,
+  session: Session,
+  connection: Connection,
+  last_applied: Option<Lsn>
+}
+
+impl Primary {
+  /// Creates a `Primary` that ships every transaction in the log of
+  /// `connection`, which must be opened with `ConnectionOptions::log`.
+  /// # Examples
+  /// ```
+  /// let options = ConnectionOptions::new()
+  ///   .home("/var/lib/users")
+  ///   .log(Logging::new().archive(false));
+  /// let mut connection = wiredtiger::open_with_options(&options).unwrap();
+  /// let primary = Primary::new(&mut connection);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if a session could not be opened
+  /// or the connection does not log its changes.
+  pub fn new(connection: &mut Connection) -> Result<Primary, String> {
+    let mut session = try!(connection.open_session());
+    // Log cursors can only be opened when logging is enabled.
+    try!(session.open_log_reader().map_err(|e|
+      format!("The primary must be opened with logging enabled: {0}", e)));
+
+    Ok(Primary{
+      session: session,
+      last_shipped: None,
+      files: HashMap::new()
+    })
+  }
+
+  /// Creates a `Primary` that ships the transactions committed after `lsn`,
+  /// usually the last position applied by the follower.
+  /// # Failures
+  /// The function returns `Err(message)` if a session could not be opened.
+  pub fn resume(connection: &mut Connection, lsn: Lsn)
+    -> Result<Primary, String> {
+    let mut primary = try!(Primary::new(connection));
+    primary.last_shipped = Some(lsn);
+    Ok(primary)
+  }
+
+  /// Returns the position of the last transaction written to the follower.
+  pub fn last_shipped(&self) -> Option<Lsn> {
+    self.last_shipped
+  }
+
+  /// Writes the transactions committed since the last call to `output` and
+  /// returns how many were shipped. Call it again to ship newer commits.
+  /// # Examples
+  /// ```
+  /// let stream = UnixStream::connect("/var/run/users-follower.sock").unwrap();
+  /// loop {
+  ///   primary.ship(&mut stream);
+  ///   thread::sleep(Duration::from_secs(1));
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the log could not be read or
+  /// the transactions could not be written to `output`.
+  pub fn ship<W: Write>(&mut self, output: &mut W) -> Result<usize, String> {
+    let mut reader = try!(self.open_reader());
+    let mut shipped = 0;
+    let mut batch: Option<Batch> = None;
+
+    while let Some(record) = try!(reader.next_record()) {
+      if self.last_shipped.map_or(false, |lsn| record.lsn <= lsn) ||
+        !is_change(&record) {
+        continue;
+      }
+
+      let uri = match try!(self.file_uri(record.file_id)) {
+        Some(uri) => uri,
+        None => continue
+      };
+      if let Some(completed) = add_change(&mut batch, record, uri) {
+        try!(self.write(output, completed));
+        shipped += 1;
+      }
+    }
+
+    if let Some(batch) = batch {
+      try!(self.write(output, batch));
+      shipped += 1;
+    }
+
+    try!(output.flush().map_err(|e| e.to_string()));
+    Ok(shipped)
+  }
+
+  fn open_reader(&mut self) -> Result<LogReader, String> {
+    match self.last_shipped {
+      Some(lsn) => self.session.open_log_reader_after(lsn),
+      None => self.session.open_log_reader()
+    }
+  }
+
+  fn write<W: Write>(&mut self, output: &mut W, batch: Batch)
+    -> Result<(), String> {
+    try!(write_batch(output, &batch).map_err(|e| e.to_string()));
+    self.last_shipped = Some(batch.lsn);
+    Ok(())
+  }
+
+  /// Returns the `file:` URI for the log file id `file_id`, or `None` for
+  /// files that are not replicated.
+  /// The metadata is read again only for file ids not seen before.
+  fn file_uri(&mut self, file_id: u32) -> Result<Option<String>, String> {
+    if !self.files.contains_key(&file_id) {
+      try!(self.load_files());
+      self.files.entry(file_id).or_insert(None);
+    }
+
+    Ok(self.files[&file_id].clone())
+  }
+
+  fn load_files(&mut self) -> Result<(), String> {
+    let metadata = try!(self.session.open_uri_cursor("metadata:",
+      &CursorOptions::new()));
+    let state_uri = format!("file:{0}.wt", STATE_TABLE);
+
+    for kvp in metadata.filter(|kvp| kvp.key.starts_with("file:")) {
+      for (key, value) in try!(wiredtiger::parse_config(&kvp.value)) {
+        if key == "id" {
+          if let Ok(id) = value.parse() {
+            let uri = Some(kvp.key.clone()).filter(|uri| *uri != state_uri);
+            self.files.insert(id, uri);
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl Follower {
+  /// Opens the follower database configured with `options`. Logging is
+  /// enabled if `options` does not configure it, so the follower can be
+  /// promoted to a primary.
+  /// # Examples
+  /// ```
+  /// let follower = Follower::open(&ConnectionOptions::new()
+  ///   .home("/var/lib/users-follower"));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the database could not be
+  /// opened or its replication state could not be read.
+  pub fn open(options: &ConnectionOptions) -> Result<Follower, String> {
+    let mut connection = try!(wiredtiger::open_with_options(
+      &options.with_log()));
+    let mut session = try!(connection.open_session());
+    try!(session.create_table(STATE_TABLE));
+    let mut state = try!(session.open_cursor(STATE_TABLE));
+    let last_applied = match state.search(LAST_APPLIED_KEY) {
+      Ok(value) => Some(try!(parse_lsn(&value))),
+      Err(_) => None
+    };
+
+    Ok(Follower{
+      state: state,
+      cursors: HashMap::new(),
+      session: session,
+      connection: connection,
+      last_applied: last_applied
+    })
+  }
+
+  /// Returns the position of the last transaction applied, used to resume
+  /// the `Primary` after a restart.
+  pub fn last_applied(&self) -> Option<Lsn> {
+    self.last_applied
+  }
+
+  /// Reads the next transaction from `input` and applies it atomically,
+  /// returning its position, or `None` once `input` is closed.
+  /// Transactions at or before `last_applied` are skipped.
+  /// # Failures
+  /// The function returns `Err(message)` if the transaction could not be
+  /// read or applied.
+  pub fn apply<R: Read>(&mut self, input: &mut R)
+    -> Result<Option<Lsn>, String> {
+    let batch = match try!(read_batch(input).map_err(|e| e.to_string())) {
+      Some(batch) => batch,
+      None => return Ok(None)
+    };
+
+    if self.last_applied.map_or(false, |lsn| batch.lsn <= lsn) {
+      return Ok(Some(batch.lsn));
+    }
+
+    try!(self.session.begin_transaction());
+    match self.apply_batch(&batch) {
+      Ok(()) => try!(self.session.commit_transaction()),
+      Err(message) => {
+        try!(self.session.rollback_transaction());
+        return Err(message);
+      }
+    }
+
+    self.last_applied = Some(batch.lsn);
+    Ok(Some(batch.lsn))
+  }
+
+  /// Applies transactions from `input` until it is closed.
+  /// # Examples
+  /// ```
+  /// let (stream, _) = listener.accept().unwrap();
+  /// follower.follow(&mut stream);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if a transaction could not be read
+  /// or applied.
+  pub fn follow<R: Read>(&mut self, input: &mut R) -> Result<(), String> {
+    while let Some(_) = try!(self.apply(input)) {}
+    Ok(())
+  }
+
+  /// Stops following the primary and returns the `Connection` so the
+  /// database can take writes.
+  pub fn promote(self) -> Connection {
+    let Follower { state, cursors, session, connection, .. } = self;
+    drop(state);
+    drop(cursors);
+    drop(session);
+    connection
+  }
+
+  fn apply_batch(&mut self, batch: &Batch) -> Result<(), String> {
+    for change in &batch.changes {
+      match *change {
+        Change::Put { ref uri, ref key, ref value } =>
+          try!(try!(self.cursor(uri)).insert_raw(key, value)),
+        Change::Remove { ref uri, ref key } =>
+          try!(try!(self.cursor(uri)).remove_raw(key))
+      }
+    }
+
+    let lsn = format!("{0}:{1}", batch.lsn.file, batch.lsn.offset);
+    self.state.insert_pair(LAST_APPLIED_KEY, &lsn)
+  }
+
+  fn cursor(&mut self, uri: &str) -> Result<&mut Cursor, String> {
+    if !self.cursors.contains_key(uri) {
+      let cursor = try!(self.session.open_uri_cursor(uri,
+        &CursorOptions::new().raw(true)));
+      self.cursors.insert(uri.to_string(), cursor);
+    }
+    Ok(self.cursors.get_mut(uri).unwrap())
+  }
+}
+
+/// Returns `true` for the records of a committed transaction that put or
+/// remove a record, the only ones shipped.
+fn is_change(record: &LogRecord) -> bool {
+  record.record_type == LogRecordType::Commit && match record.operation {
+    LogOperation::RowPut | LogOperation::ColumnPut |
+    LogOperation::RowRemove | LogOperation::ColumnRemove => true,
+    _ => false
+  }
+}
+
+/// Adds the change `record` makes to the object `uri` to `batch`, starting
+/// a new batch when `record` belongs to another transaction, and returns the
+/// batch completed that way.
+fn add_change(batch: &mut Option<Batch>, record: LogRecord, uri: String)
+  -> Option<Batch> {
+  let completed = if batch.as_ref().map_or(false, |b| b.lsn != record.lsn) {
+    batch.take()
+  } else {
+    None
+  };
+  let change = match record.operation {
+    LogOperation::RowRemove | LogOperation::ColumnRemove => Change::Remove{
+      uri: uri,
+      key: record.key
+    },
+    _ => Change::Put{
+      uri: uri,
+      key: record.key,
+      value: record.value
+    }
+  };
+
+  batch.get_or_insert(Batch{
+    lsn: record.lsn,
+    changes: Vec::new()
+  }).changes.push(change);
+  completed
+}
+
+fn parse_lsn(value: &str) -> Result<Lsn, String> {
+  let mut parts = value.splitn(2, ':').map(|p| p.parse::<u32>());
+  match (parts.next(), parts.next()) {
+    (Some(Ok(file)), Some(Ok(offset))) => Ok(Lsn{
+      file: file,
+      offset: offset
+    }),
+    _ => Err(format!("Invalid log position {0}", value))
+  }
+}
+
+fn write_batch<W: Write>(output: &mut W, batch: &Batch)
+  -> ::std::io::Result<()> {
+  try!(output.write_all(&batch.lsn.file.to_le_bytes()));
+  try!(output.write_all(&batch.lsn.offset.to_le_bytes()));
+  try!(output.write_all(&(batch.changes.len() as u32).to_le_bytes()));
+
+  for change in &batch.changes {
+    match *change {
+      Change::Put { ref uri, ref key, ref value } => {
+        try!(output.write_all(&[OP_PUT]));
+        try!(write_bytes(output, uri.as_bytes()));
+        try!(write_bytes(output, key));
+        try!(write_bytes(output, value));
+      }
+      Change::Remove { ref uri, ref key } => {
+        try!(output.write_all(&[OP_REMOVE]));
+        try!(write_bytes(output, uri.as_bytes()));
+        try!(write_bytes(output, key));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn write_bytes<W: Write>(output: &mut W, bytes: &[u8])
+  -> ::std::io::Result<()> {
+  try!(output.write_all(&(bytes.len() as u32).to_le_bytes()));
+  output.write_all(bytes)
+}
+
+fn read_batch<R: Read>(input: &mut R) -> ::std::io::Result<Option<Batch>> {
+  let file = match read_u32(input) {
+    Ok(file) => file,
+    Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+    Err(e) => return Err(e)
+  };
+  let offset = try!(read_u32(input));
+  let count = try!(read_u32(input));
+  let mut changes = Vec::with_capacity(count as usize);
+
+  for _ in 0..count {
+    let mut op = [0u8; 1];
+    try!(input.read_exact(&mut op));
+    let uri = String::from_utf8_lossy(&try!(read_bytes(input))).to_string();
+    let key = try!(read_bytes(input));
+    changes.push(match op[0] {
+      OP_PUT => Change::Put{
+        uri: uri,
+        key: key,
+        value: try!(read_bytes(input))
+      },
+      OP_REMOVE => Change::Remove{
+        uri: uri,
+        key: key
+      },
+      other => return Err(::std::io::Error::new(ErrorKind::InvalidData,
+        format!("Unknown replication operation {0}", other)))
+    });
+  }
+
+  Ok(Some(Batch{
+    lsn: Lsn{
+      file: file,
+      offset: offset
+    },
+    changes: changes
+  }))
+}
+
+fn read_u32<R: Read>(input: &mut R) -> ::std::io::Result<u32> {
+  let mut bytes = [0u8; 4];
+  try!(input.read_exact(&mut bytes));
+  Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_bytes<R: Read>(input: &mut R) -> ::std::io::Result<Vec<u8>> {
+  let len = try!(read_u32(input));
+  let mut bytes = vec![0u8; len as usize];
+  try!(input.read_exact(&mut bytes));
+  Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Batch, Change, add_change, is_change, parse_lsn, read_batch,
+    write_batch};
+  use log_reader::{Lsn, LogOperation, LogRecord, LogRecordType};
+
+  /// Returns a record as the log cursor decodes it from the raw record type
+  /// and operation.
+  fn record(lsn: Lsn, record_type: u32, operation: u32, key: &[u8])
+    -> LogRecord {
+    LogRecord{
+      lsn: lsn,
+      op_count: 0,
+      txn_id: 7,
+      record_type: LogRecordType::from_raw(record_type),
+      operation: LogOperation::from_raw(operation),
+      file_id: 2,
+      key: key.to_vec(),
+      value: b"admin\0".to_vec()
+    }
+  }
+
+  #[test]
+  fn batches_the_changes_of_commit_records() {
+    let first = Lsn{ file: 1, offset: 128 };
+    let second = Lsn{ file: 1, offset: 256 };
+    // WT_LOGREC_COMMIT with WT_LOGOP_ROW_PUT and WT_LOGOP_ROW_REMOVE.
+    let put = record(first, 1, 4, b"alice\0");
+    let remove = record(first, 1, 5, b"bob\0");
+    let next = record(second, 1, 4, b"carol\0");
+    assert!(is_change(&put) && is_change(&remove) && is_change(&next));
+    // WT_LOGREC_CHECKPOINT, WT_LOGREC_FILE_SYNC and WT_LOGREC_MESSAGE.
+    for record_type in 0..4 {
+      if record_type != 1 {
+        assert!(!is_change(&record(first, record_type, 4, b"")));
+      }
+    }
+    // WT_LOGOP_ROW_TRUNCATE.
+    assert!(!is_change(&record(first, 1, 6, b"")));
+
+    let uri = "file:users.wt".to_string();
+    let mut batch = None;
+    assert_eq!(add_change(&mut batch, put, uri.clone()), None);
+    assert_eq!(add_change(&mut batch, remove, uri.clone()), None);
+    assert_eq!(add_change(&mut batch, next, uri.clone()), Some(Batch{
+      lsn: first,
+      changes: vec![
+        Change::Put{
+          uri: uri.clone(),
+          key: b"alice\0".to_vec(),
+          value: b"admin\0".to_vec()
+        },
+        Change::Remove{
+          uri: uri.clone(),
+          key: b"bob\0".to_vec()
+        }
+      ]
+    }));
+    assert_eq!(batch.map(|b| b.lsn), Some(second));
+  }
+
+  #[test]
+  fn batch_round_trip() {
+    let batch = Batch{
+      lsn: Lsn{ file: 3, offset: 1280 },
+      changes: vec![
+        Change::Put{
+          uri: "file:users.wt".to_string(),
+          key: b"alice\0".to_vec(),
+          value: vec![]
+        },
+        Change::Remove{
+          uri: "file:users.wt".to_string(),
+          key: vec![0, 255]
+        }
+      ]
+    };
+    let mut bytes = Vec::new();
+    write_batch(&mut bytes, &batch).unwrap();
+    write_batch(&mut bytes, &batch).unwrap();
+
+    let mut input = &bytes[..];
+    assert_eq!(read_batch(&mut input).unwrap(), Some(batch.clone()));
+    assert_eq!(read_batch(&mut input).unwrap(), Some(batch));
+    assert_eq!(read_batch(&mut input).unwrap(), None);
+  }
+
+  #[test]
+  fn read_batch_rejects_truncated_and_unknown_input() {
+    let batch = Batch{
+      lsn: Lsn{ file: 1, offset: 0 },
+      changes: vec![Change::Remove{
+        uri: "file:users.wt".to_string(),
+        key: b"bob".to_vec()
+      }]
+    };
+    let mut bytes = Vec::new();
+    write_batch(&mut bytes, &batch).unwrap();
+
+    let mut truncated = &bytes[..bytes.len() - 1];
+    assert!(read_batch(&mut truncated).is_err());
+
+    // The operation follows the file, offset and change count.
+    bytes[12] = 9;
+    let mut unknown = &bytes[..];
+    assert!(read_batch(&mut unknown).is_err());
+  }
+
+  #[test]
+  fn parses_log_positions() {
+    assert_eq!(parse_lsn("3:1280"), Ok(Lsn{ file: 3, offset: 1280 }));
+    assert!(parse_lsn("3").is_err());
+    assert!(parse_lsn("3:").is_err());
+    assert!(parse_lsn("a:1").is_err());
+    assert!(parse_lsn("1:-1").is_err());
+  }
+}
@@ -0,0 +1,89 @@
+use wiredtiger::{Session, CursorOptions, parse_config};
+
+/// The schema of a table as stored in the database metadata.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableSchema {
+  pub name: String,
+  pub key_format: String,
+  pub value_format: String,
+  pub columns: Vec<String>,
+  pub colgroups: Vec<String>,
+  pub app_metadata: String
+}
+
+impl TableSchema {
+  fn from_config(name: &str, config: &str) -> Result<TableSchema, String> {
+    let mut schema = TableSchema{
+      name: name.to_string(),
+      ..TableSchema::default()
+    };
+
+    for (key, value) in try!(parse_config(config)) {
+      match &key[..] {
+        "key_format" => schema.key_format = value,
+        "value_format" => schema.value_format = value,
+        "columns" => schema.columns = try!(config_list(&value)),
+        "colgroups" => schema.colgroups = try!(config_list(&value)),
+        "app_metadata" => schema.app_metadata = value,
+        _ => ()
+      }
+    }
+
+    Ok(schema)
+  }
+}
+
+fn config_list(value: &str) -> Result<Vec<String>, String> {
+  let items = try!(parse_config(value));
+  Ok(items.into_iter().map(|(key, _)| key).collect())
+}
+
+impl Session {
+  /// Returns the names of the tables in the database.
+  /// # Examples
+  /// ```
+  /// let tables = session.list_tables();
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the metadata could not be read.
+  pub fn list_tables(&mut self) -> Result<Vec<String>, String> {
+    self.list_metadata("table:")
+  }
+
+  /// Returns the names of the indexes of the table `table_name`.
+  /// # Examples
+  /// ```
+  /// let indexes = session.list_indexes("users");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the metadata could not be read.
+  pub fn list_indexes(&mut self, table_name: &str)
+    -> Result<Vec<String>, String> {
+    self.list_metadata(&format!("index:{0}:", table_name))
+  }
+
+  /// Returns the schema of the table `name`.
+  /// # Examples
+  /// ```
+  /// let schema = session.table_schema("users");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table does not exist or its
+  /// metadata could not be parsed.
+  pub fn table_schema(&mut self, name: &str) -> Result<TableSchema, String> {
+    let mut metadata = try!(self.open_uri_cursor("metadata:",
+      &CursorOptions::new()));
+    let config = try!(metadata.search(&format!("table:{0}", name)));
+    TableSchema::from_config(name, &config)
+  }
+
+  fn list_metadata(&mut self, prefix: &str) -> Result<Vec<String>, String> {
+    let metadata = try!(self.open_uri_cursor("metadata:",
+      &CursorOptions::new()));
+
+    Ok(metadata
+      .filter(|kvp| kvp.key.starts_with(prefix))
+      .map(|kvp| kvp.key[prefix.len()..].to_string())
+      .collect())
+  }
+}
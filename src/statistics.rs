@@ -0,0 +1,321 @@
+extern crate libc;
+
+use wiredtiger::{Connection, Session, Cursor, CursorOptions, StatisticsLog,
+  WT_NOTFOUND, get_error, string_from_ptr};
+
+use self::libc::c_char;
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::ptr;
+
+/// The statistics of a connection or table, by description, such as
+/// `cache: bytes currently in the cache`.
+///
+/// Statistics are only gathered when the connection is opened with
+/// `ConnectionOptions::statistics`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+  values: BTreeMap<String, i64>
+}
+
+impl Statistics {
+  /// Returns the value of the statistic described as `description`.
+  pub fn get(&self, description: &str) -> Option<i64> {
+    self.values.get(description).cloned()
+  }
+
+  /// Iterates over the descriptions and values of every statistic.
+  pub fn iter(&self) -> btree_map::Iter<'_, String, i64> {
+    self.values.iter()
+  }
+
+  /// The bytes of data held in the cache.
+  pub fn cache_bytes(&self) -> Option<i64> {
+    self.first_of(&["cache: bytes currently in the cache"])
+  }
+
+  /// The maximum size of the cache.
+  pub fn cache_max_bytes(&self) -> Option<i64> {
+    self.first_of(&["cache: maximum bytes configured"])
+  }
+
+  /// The pages evicted from the cache, modified or not.
+  pub fn pages_evicted(&self) -> Option<i64> {
+    let modified = self.get("cache: modified pages evicted");
+    let unmodified = self.get("cache: unmodified pages evicted");
+    match (modified, unmodified) {
+      (None, None) => None,
+      _ => Some(modified.unwrap_or(0) + unmodified.unwrap_or(0))
+    }
+  }
+
+  /// The transactions committed.
+  pub fn transactions_committed(&self) -> Option<i64> {
+    self.first_of(&["transaction: transactions committed"])
+  }
+
+  /// The transactions rolled back.
+  pub fn transactions_rolled_back(&self) -> Option<i64> {
+    self.first_of(&["transaction: transactions rolled back"])
+  }
+
+  /// The calls to `Cursor::insert`.
+  pub fn cursor_inserts(&self) -> Option<i64> {
+    self.first_of(&["cursor: cursor insert calls", "cursor: insert calls"])
+  }
+
+  /// The calls to `Cursor::search`.
+  pub fn cursor_searches(&self) -> Option<i64> {
+    self.first_of(&["cursor: cursor search calls", "cursor: search calls"])
+  }
+
+  /// The cursor update calls.
+  pub fn cursor_updates(&self) -> Option<i64> {
+    self.first_of(&["cursor: cursor update calls", "cursor: update calls"])
+  }
+
+  /// The calls to `Cursor::remove`.
+  pub fn cursor_removes(&self) -> Option<i64> {
+    self.first_of(&["cursor: cursor remove calls", "cursor: remove calls"])
+  }
+
+  /// The bytes read by the block manager.
+  pub fn block_bytes_read(&self) -> Option<i64> {
+    self.first_of(&["block-manager: bytes read"])
+  }
+
+  /// The bytes written by the block manager.
+  pub fn block_bytes_written(&self) -> Option<i64> {
+    self.first_of(&["block-manager: bytes written"])
+  }
+
+  /// Connection and table statistics describe the same counter differently,
+  /// so accessors look for each description in turn.
+  fn first_of(&self, descriptions: &[&str]) -> Option<i64> {
+    descriptions.iter().filter_map(|d| self.get(d)).next()
+  }
+}
+
+/// The statistics of a source written at once to a statistics log file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatisticsLogRecord {
+  /// When the statistics were written, in the format of
+  /// `StatisticsLog::timestamp`.
+  pub timestamp: String,
+  /// The database home for the connection statistics, or the URI of the
+  /// object the statistics belong to.
+  pub source: String,
+  pub statistics: Statistics
+}
+
+impl StatisticsLog {
+  /// Reads the statistics log file at `path`, written with these options,
+  /// and returns its records in the order they were written.
+  /// # Examples
+  /// ```
+  /// let log = StatisticsLog::new(Duration::from_secs(30));
+  /// for record in log.read(Path::new("db/WiredTigerStat.18.10")).unwrap() {
+  ///   println!("{} {:?}", record.timestamp, record.statistics.cache_bytes());
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the file could not be read or a
+  /// line is not a statistic.
+  pub fn read(&self, path: &Path) -> Result<Vec<StatisticsLogRecord>, String> {
+    let file = try!(File::open(path).map_err(|e| e.to_string()));
+    // Each line is the timestamp, the value, the source and the description,
+    // and the timestamp spans as many words as its format.
+    let stamp_words = self.timestamp_format().split_whitespace().count();
+    let mut records: Vec<StatisticsLogRecord> = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+      let line = try!(line.map_err(|e| e.to_string()));
+      let words: Vec<&str> = line.split_whitespace().collect();
+      if words.is_empty() {
+        continue;
+      }
+      if words.len() < stamp_words + 3 {
+        return Err(format!("Invalid statistics log line: {0}", line));
+      }
+
+      let timestamp = words[..stamp_words].join(" ");
+      let value = try!(words[stamp_words].parse::<i64>()
+        .map_err(|_| format!("Invalid statistics log line: {0}", line)));
+      let source = words[stamp_words + 1];
+      let description = words[stamp_words + 2..].join(" ");
+
+      let same_record = records.last().map_or(false, |r| {
+        r.timestamp == timestamp && r.source == source
+      });
+      if !same_record {
+        records.push(StatisticsLogRecord{
+          timestamp: timestamp,
+          source: source.to_string(),
+          statistics: Statistics::default()
+        });
+      }
+      if let Some(record) = records.last_mut() {
+        record.statistics.values.insert(description, value);
+      }
+    }
+
+    Ok(records)
+  }
+}
+
+impl Connection {
+  /// Returns the statistics of the connection.
+  /// # Examples
+  /// ```
+  /// let options = ConnectionOptions::new()
+  ///   .statistics(&[CursorStatistics::Fast]);
+  /// let mut connection = wiredtiger::open_with_options(&options).unwrap();
+  /// let cached = connection.stats().unwrap().cache_bytes();
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the statistics could not be read.
+  pub fn stats(&mut self) -> Result<Statistics, String> {
+    let mut session = try!(self.open_session());
+    session.stats()
+  }
+}
+
+impl Session {
+  /// Returns the statistics of the connection the session belongs to.
+  /// Wired Tiger does not keep statistics per session.
+  /// # Failures
+  /// The function returns `Err(message)` if the statistics could not be read.
+  pub fn stats(&mut self) -> Result<Statistics, String> {
+    self.read_statistics("statistics:")
+  }
+
+  /// Returns the statistics of the table `table_name`.
+  /// # Examples
+  /// ```
+  /// let written = session.table_stats("users").unwrap().cursor_inserts();
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the statistics could not be read.
+  pub fn table_stats(&mut self, table_name: &str)
+    -> Result<Statistics, String> {
+    self.read_statistics(&format!("statistics:table:{0}", table_name))
+  }
+
+  fn read_statistics(&mut self, uri: &str) -> Result<Statistics, String> {
+    let mut cursor = try!(self.open_uri_cursor(uri, &CursorOptions::new()));
+    cursor.read_statistics()
+  }
+}
+
+impl Cursor {
+  /// Reads every statistic of a `statistics:` cursor, such as one opened
+  /// with `CursorOptions::statistics` to pick the statistics gathered.
+  /// # Examples
+  /// ```
+  /// let options = CursorOptions::new().statistics(&[CursorStatistics::Clear]);
+  /// let mut cursor = session.open_uri_cursor("statistics:table:users",
+  ///   &options).unwrap();
+  /// let inserts = cursor.read_statistics().unwrap().cursor_inserts();
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor is not a statistics
+  /// cursor or the statistics could not be read.
+  pub fn read_statistics(&mut self) -> Result<Statistics, String> {
+    let format = unsafe { string_from_ptr((*self.wt_cursor).value_format) };
+    if format != "SSq" {
+      return Err("Cursor is not a statistics cursor".to_string());
+    }
+    let mut statistics = Statistics::default();
+
+    while let Some((description, value)) = try!(next_statistic(self)) {
+      statistics.values.insert(description, value);
+    }
+
+    Ok(statistics)
+  }
+}
+
+/// Moves `cursor` to the next statistic and returns its description and
+/// value, or `None` after the last one. The value of a statistics cursor is
+/// the description, the printable value and the value.
+fn next_statistic(cursor: &Cursor) -> Result<Option<(String, i64)>, String> {
+  let wt_cursor = cursor.wt_cursor;
+  unsafe {
+    let (next, get_value) = match ((*wt_cursor).next, (*wt_cursor).get_value) {
+      (Some(next), Some(get_value)) => (next, get_value),
+      _ => return Err("Failed to get next".to_string())
+    };
+    let ret = next(wt_cursor);
+
+    if ret == WT_NOTFOUND {
+      return Ok(None);
+    }
+    if ret != 0 {
+      return Err(get_error(ret));
+    }
+
+    let mut description: *const c_char = ptr::null();
+    let mut printable: *const c_char = ptr::null();
+    let mut value: i64 = 0;
+    let ret = get_value(wt_cursor, &mut description, &mut printable,
+      &mut value);
+
+    if ret != 0 {
+      return Err(get_error(ret));
+    }
+
+    Ok(Some((string_from_ptr(description), value)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiredtiger::StatisticsLog;
+  use std::env;
+  use std::fs;
+  use std::process;
+  use std::time::Duration;
+
+  #[test]
+  fn reads_statistics_log_records() {
+    let path = env::temp_dir()
+      .join(format!("wiredtiger-statistics-{0}.log", process::id()));
+    fs::write(&path, "\
+      Jan 02 03:04:05 1024 /db cache: bytes currently in the cache\n\
+      Jan 02 03:04:05 7 /db cache: modified pages evicted\n\
+      Jan 02 03:04:05 12 table:users cursor: insert calls\n\
+      \n\
+      Jan 02 03:04:35 2048 /db cache: bytes currently in the cache\n").unwrap();
+
+    let log = StatisticsLog::new(Duration::from_secs(30));
+    let records = log.read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].timestamp, "Jan 02 03:04:05");
+    assert_eq!(records[0].source, "/db");
+    assert_eq!(records[0].statistics.cache_bytes(), Some(1024));
+    assert_eq!(records[0].statistics.pages_evicted(), Some(7));
+    assert_eq!(records[1].source, "table:users");
+    assert_eq!(records[1].statistics.cursor_inserts(), Some(12));
+    assert_eq!(records[1].statistics.cache_bytes(), None);
+    assert_eq!(records[2].timestamp, "Jan 02 03:04:35");
+    assert_eq!(records[2].statistics.cache_bytes(), Some(2048));
+  }
+
+  #[test]
+  fn rejects_lines_that_are_not_statistics() {
+    let path = env::temp_dir()
+      .join(format!("wiredtiger-statistics-invalid-{0}.log", process::id()));
+    let log = StatisticsLog::new(Duration::from_secs(30));
+
+    fs::write(&path, "Jan 02 03:04:05 many /db cache: pages\n").unwrap();
+    assert!(log.read(&path).is_err());
+    fs::write(&path, "Jan 02 03:04:05 12\n").unwrap();
+    assert!(log.read(&path).is_err());
+    fs::remove_file(&path).unwrap();
+  }
+}
@@ -1,24 +1,132 @@
 extern crate libc;
 
-use wiredtiger_def::{WT_CONNECTION,WT_SESSION,WT_CURSOR,
-  wiredtiger_open,wiredtiger_strerror};
+use wiredtiger_def::{WT_CONNECTION,WT_SESSION,WT_CURSOR,WT_ITEM,
+  WT_CONFIG_ITEM,WT_CONFIG_PARSER,WT_EVENT_HANDLER,wiredtiger_open,wiredtiger_strerror,
+  wiredtiger_config_parser_open};
 
 use self::libc::{c_int, c_char};
 use std::ffi::{CStr,CString};
-use std::{ptr,mem,str};
+use std::{ptr,slice};
+use std::mem::MaybeUninit;
 use std::ops::{Drop};
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use event_handler::{EventHandler, EventBridge, WT_ERROR};
 
-unsafe fn string_from_ptr(ptr: *const c_char) -> String {
-  let slice = CStr::from_ptr(ptr);
-  str::from_utf8(slice.to_bytes()).unwrap().to_string()
+pub(crate) const WT_DUPLICATE_KEY: c_int = -31801;
+pub(crate) const WT_NOTFOUND: c_int = -31803;
+
+/// Returns the nul terminated string at `ptr`, or an empty string when it is
+/// null.
+pub(crate) unsafe fn string_from_ptr(ptr: *const c_char) -> String {
+  if ptr.is_null() {
+    return String::new();
+  }
+  CStr::from_ptr(ptr).to_string_lossy().to_string()
 }
 
-unsafe fn get_error(code: c_int) -> String {
+pub(crate) unsafe fn get_error(code: c_int) -> String {
   string_from_ptr(wiredtiger_strerror(code))
 }
 
-fn c_str(val: &str) -> CString{
-  CString::new(val).unwrap()
+/// Logs the failure of the `component` Wired Tiger called back into and
+/// returns the error to hand back to Wired Tiger.
+pub(crate) fn report(component: &str, message: String) -> c_int {
+  error!(target: "wiredtiger", "{} failed: {}", component, message);
+  WT_ERROR
+}
+
+pub(crate) fn c_str(val: &str) -> Result<CString, String>{
+  CString::new(val).map_err(|_| format!("{0:?} contains a nul byte", val))
+}
+
+/// Returns the bytes referenced by `item`, which must outlive the slice.
+pub(crate) unsafe fn slice_from_item<'a>(item: *const WT_ITEM) -> &'a [u8] {
+  if item.is_null() || (*item).data.is_null() {
+    return &[];
+  }
+  slice::from_raw_parts((*item).data as *const u8, (*item).size as usize)
+}
+
+/// Returns the packed string `bytes`, without its trailing nul byte.
+fn string_from_bytes(bytes: &[u8]) -> String {
+  String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string()
+}
+
+pub(crate) unsafe fn string_from_config_item(item: *const WT_CONFIG_ITEM)
+  -> String {
+  if item.is_null() || (*item)._str.is_null() {
+    return String::new();
+  }
+  let item = &*item;
+  let bytes = slice::from_raw_parts(item._str as *const u8, item.len as usize);
+  String::from_utf8_lossy(bytes).to_string()
+}
+
+/// Parses a Wired Tiger configuration string into its top level key/value
+/// pairs. Nested values keep their enclosing brackets removed so they can be
+/// parsed again.
+pub(crate) fn parse_config(config: &str) -> Result<Vec<(String, String)>, String> {
+  let value = strip_brackets(config.trim());
+  let c_config = try!(c_str(value));
+  let mut pairs = Vec::new();
+
+  unsafe {
+    let mut parser: *mut WT_CONFIG_PARSER = ptr::null_mut();
+    let ret = wiredtiger_config_parser_open(ptr::null_mut(),
+      c_config.as_ptr(),
+      value.len() as _,
+      &mut parser);
+
+    if ret != 0 {
+      return Err(get_error(ret));
+    }
+
+    let next = match (*parser).next {
+      Some(next) => next,
+      None => return Err("Failed to get next".to_string())
+    };
+
+    let mut key = WT_CONFIG_ITEM::default();
+    let mut item = WT_CONFIG_ITEM::default();
+    let mut ret = next(parser, &mut key, &mut item);
+    while ret == 0 {
+      pairs.push((string_from_config_item(&key),
+        string_from_config_item(&item)));
+      ret = next(parser, &mut key, &mut item);
+    }
+
+    if let Some(close) = (*parser).close {
+      close(parser);
+    }
+
+    if ret != WT_NOTFOUND {
+      return Err(get_error(ret));
+    }
+  }
+
+  Ok(pairs)
+}
+
+/// Removes the brackets enclosing a nested configuration `value`, leaving
+/// other values, including unbalanced ones, for the parser to report.
+fn strip_brackets(value: &str) -> &str {
+  let enclosed = (value.starts_with('(') && value.ends_with(')')) ||
+    (value.starts_with('[') && value.ends_with(']'));
+  if enclosed && value.len() > 1 {
+    &value[1..value.len() - 1]
+  } else {
+    value
+  }
+}
+
+pub(crate) fn item_from_bytes(bytes: &[u8]) -> WT_ITEM {
+  let mut item = WT_ITEM::default();
+  item.data = bytes.as_ptr() as *const _;
+  item.size = bytes.len() as _;
+  item
 }
 
 /// Opens a Wired Tiger connection and returns a new `Connection`.
@@ -36,38 +144,215 @@ fn c_str(val: &str) -> CString{
 /// # Failures
 /// The function returns `Err(message)` if the connection failed to open.
 pub fn open() -> Result<Connection, String> {
-  let action = c_str("create");
+  open_with_options(&ConnectionOptions::new())
+}
+
+/// Opens a Wired Tiger connection for the database in the `home` directory
+/// and returns a new `Connection`.
+/// # Examples
+/// ```
+/// let connection = wiredtiger::open_at("/var/lib/users");
+/// ```
+/// # Failures
+/// The function returns `Err(message)` if the connection failed to open.
+pub fn open_at(home: &str) -> Result<Connection, String> {
+  open_with_options(&ConnectionOptions::new().home(home))
+}
+
+/// Opens a Wired Tiger connection configured with `options` and returns a
+/// new `Connection`.
+/// # Examples
+/// ```
+/// let options = ConnectionOptions::new()
+///   .home("/var/lib/users")
+///   .event_handler(LogEventHandler);
+/// let connection = wiredtiger::open_with_options(&options);
+/// ```
+/// # Failures
+/// The function returns `Err(message)` if the connection failed to open.
+pub fn open_with_options(options: &ConnectionOptions)
+  -> Result<Connection, String> {
+  let action = try!(c_str(&options.to_config()));
+  let home = try!(options.home.as_ref().map(|h| c_str(h)).transpose());
+  let mut event_bridge = options.event_handler.as_ref()
+    .map(|h| EventBridge::new(h.clone()));
   unsafe {
-    let mut connection: *mut WT_CONNECTION = mem::uninitialized();
+    let mut connection = MaybeUninit::<*mut WT_CONNECTION>::uninit();
 
-    let ret = wiredtiger_open(ptr::null(),
-      ptr::null_mut(),
+    let ret = wiredtiger_open(home.as_ref().map_or(ptr::null(), |h| h.as_ptr()),
+      event_bridge.as_mut().map_or(ptr::null_mut(), |b| b.as_ptr()),
       action.as_ptr(),
-      &mut connection);
+      connection.as_mut_ptr());
 
     if ret != 0{
       return Err(get_error(ret));
     }
+    let connection = connection.assume_init();
 
     Ok(Connection{
-      wt_con: connection
+      wt_con: connection,
+      handle: Rc::new(ConnectionHandle{
+        wt_con: connection,
+        owned: true,
+        event_bridge: event_bridge
+      })
     })
   }
 }
 
+/// Options used to configure a `Connection` when it is opened.
+#[derive(Clone)]
+pub struct ConnectionOptions {
+  home: Option<String>,
+  create: bool,
+  async_ops: Option<(u32, u32)>,
+  extensions: Vec<(String, Option<String>)>,
+  statistics: Vec<CursorStatistics>,
+  statistics_log: Option<StatisticsLog>,
+  log: Option<Logging>,
+  event_handler: Option<Arc<dyn EventHandler + Send + Sync>>
+}
+
+/// Options used to configure a table when it is created.
+/// # Examples
+/// ```
+/// let options = TableOptions::new().key_format("r").collator("reverse");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TableOptions {
+  key_format: String,
+  value_format: String,
+  columns: Vec<String>,
+  collator: Option<String>,
+  block_compressor: Option<String>,
+  data_source: Option<String>,
+  app_metadata: Option<String>
+}
+
+/// Options used to configure an index when it is created.
+/// # Examples
+/// ```
+/// let options = IndexOptions::new().columns(&["city"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IndexOptions {
+  columns: Vec<String>,
+  extractor: Option<String>,
+  app_metadata: Option<String>
+}
+
+/// Options used to write the statistics of a connection to log files
+/// periodically, read back with `StatisticsLog::read`.
+/// # Examples
+/// ```
+/// let options = ConnectionOptions::new()
+///   .statistics(&[CursorStatistics::Fast])
+///   .statistics_log(StatisticsLog::new(Duration::from_secs(30))
+///     .sources(&["table:users"]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatisticsLog {
+  wait: Duration,
+  path: Option<String>,
+  sources: Vec<String>,
+  timestamp: String,
+  on_close: bool
+}
+
+/// Options used to write every change to the database log, which log
+/// readers, incremental backups and replication read back.
+/// # Examples
+/// ```
+/// let options = ConnectionOptions::new()
+///   .log(Logging::new().archive(false));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Logging {
+  archive: bool,
+  path: Option<String>
+}
+
 /// Represents a Wired Tiger connection.
+///
+/// The connection stays open until it and every `Session` opened from it
+/// have been dropped, so they can be dropped in any order.
 pub struct Connection {
-  wt_con: *mut WT_CONNECTION
+  pub(crate) wt_con: *mut WT_CONNECTION,
+  handle: Rc<ConnectionHandle>
 }
 
 /// Represents a Wired Tiger session.
+///
+/// The session stays open until it and every `Cursor` opened from it have
+/// been dropped.
 pub struct Session {
-  wt_session: *mut WT_SESSION
+  pub(crate) wt_session: *mut WT_SESSION,
+  handle: Rc<SessionHandle>
 }
 
 /// Represents a Wired Tiger cursor.
 pub struct Cursor {
-  wt_cursor: *mut WT_CURSOR
+  pub(crate) wt_cursor: *mut WT_CURSOR,
+  options: CursorOptions,
+  _session: Rc<SessionHandle>,
+  /// The last key and value set, which Wired Tiger reads until the cursor is
+  /// positioned again.
+  key: Vec<u8>,
+  value: Vec<u8>
+}
+
+/// Owns a Wired Tiger connection, closing it when the last `Connection` or
+/// `Session` sharing it is dropped.
+struct ConnectionHandle {
+  wt_con: *mut WT_CONNECTION,
+  /// `false` for connections this crate did not open, which are left open.
+  owned: bool,
+  /// Dropped after the connection is closed, as Wired Tiger reports events
+  /// until then.
+  event_bridge: Option<Box<EventBridge<'static>>>
+}
+
+/// Owns a Wired Tiger session, closing it when the last `Session` or
+/// `Cursor` sharing it is dropped. It keeps its connection open.
+struct SessionHandle {
+  wt_session: *mut WT_SESSION,
+  connection: Rc<ConnectionHandle>
+}
+
+/// Format used by a `Cursor` opened with the `dump` option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DumpFormat {
+  Hex,
+  Json,
+  Print
+}
+
+/// Statistics gathered by a `Connection` or by a `Cursor` opened with the
+/// `statistics` option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CursorStatistics {
+  All,
+  Fast,
+  Clear
+}
+
+/// Options used to configure a `Cursor` when it is opened.
+/// # Examples
+/// ```
+/// let options = CursorOptions::new().readonly(true).checkpoint("nightly");
+/// session.open_cursor_with_options("users", &options);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CursorOptions {
+  raw: bool,
+  readonly: bool,
+  checkpoint: Option<String>,
+  append: bool,
+  overwrite: Option<bool>,
+  next_random: bool,
+  statistics: Vec<CursorStatistics>,
+  dump: Option<DumpFormat>,
+  target: Vec<String>
 }
 
 /// A key value pair that can be used for Wired Tiger tables with that structure
@@ -76,10 +361,12 @@ pub struct KeyValuePair {
   pub value: String
 }
 
-impl Drop for Connection {
-  /// When the connection is dropped the underlying connection is closed.
-  /// All other components related to the connection are no longer usable.
+impl Drop for ConnectionHandle {
+  /// Closes the underlying connection once nothing uses it anymore.
   fn drop(&mut self) {
+    if !self.owned {
+      return;
+    }
     unsafe{
       match (*self.wt_con).close {
         Some(close) => { close(self.wt_con, ptr::null_mut()); }
@@ -89,7 +376,421 @@ impl Drop for Connection {
   }
 }
 
+impl TableOptions {
+  /// Returns the default options, a table with string keys and values.
+  pub fn new() -> TableOptions {
+    TableOptions{
+      key_format: "S".to_string(),
+      value_format: "S".to_string(),
+      columns: Vec::new(),
+      collator: None,
+      block_compressor: None,
+      data_source: None,
+      app_metadata: None
+    }
+  }
+
+  /// The format of the keys, such as `S` for strings or `r` for record
+  /// numbers.
+  pub fn key_format(mut self, format: &str) -> TableOptions {
+    self.key_format = format.to_string();
+    self
+  }
+
+  /// The format of the values.
+  pub fn value_format(mut self, format: &str) -> TableOptions {
+    self.value_format = format.to_string();
+    self
+  }
+
+  /// Names the key columns followed by the value columns, one per field of
+  /// the formats, so indexes can be created on the value columns.
+  /// # Examples
+  /// ```
+  /// let options = TableOptions::new().columns(&["name", "city"]);
+  /// session.create_table_with_options("users", &options);
+  /// session.create_index("users", "by_city",
+  ///   &IndexOptions::new().columns(&["city"]));
+  /// ```
+  pub fn columns(mut self, columns: &[&str]) -> TableOptions {
+    self.columns = columns.iter().map(|c| c.to_string()).collect();
+    self
+  }
+
+  /// Orders the keys with the collator registered as `name` instead of
+  /// comparing their bytes.
+  pub fn collator(mut self, name: &str) -> TableOptions {
+    self.collator = Some(name.to_string());
+    self
+  }
+
+  /// Compresses the blocks written to disk with the compressor registered
+  /// as `name`.
+  pub fn block_compressor(mut self, name: &str) -> TableOptions {
+    self.block_compressor = Some(name.to_string());
+    self
+  }
+
+  /// Stores the table in the data source registered for the `prefix:` URIs
+  /// instead of a file.
+  pub fn data_source(mut self, prefix: &str) -> TableOptions {
+    self.data_source = Some(prefix.to_string());
+    self
+  }
+
+  /// Application data stored with the table, also passed to the collator
+  /// when it is customized for the table.
+  pub fn app_metadata(mut self, app_metadata: &str) -> TableOptions {
+    self.app_metadata = Some(app_metadata.to_string());
+    self
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = vec![
+      format!("key_format={0}", self.key_format),
+      format!("value_format={0}", self.value_format)
+    ];
+
+    if !self.columns.is_empty() {
+      config.push(format!("columns=({0})", self.columns.join(",")));
+    }
+    if let Some(ref collator) = self.collator {
+      config.push(format!("collator={0}", collator));
+    }
+    if let Some(ref block_compressor) = self.block_compressor {
+      config.push(format!("block_compressor={0}", block_compressor));
+    }
+    if let Some(ref data_source) = self.data_source {
+      config.push(format!("type={0}", data_source));
+    }
+    if let Some(ref app_metadata) = self.app_metadata {
+      config.push(format!("app_metadata=\"{0}\"", app_metadata));
+    }
+
+    config.join(",")
+  }
+}
+
+impl IndexOptions {
+  pub fn new() -> IndexOptions {
+    IndexOptions::default()
+  }
+
+  /// The table columns the index keys are made of.
+  pub fn columns(mut self, columns: &[&str]) -> IndexOptions {
+    self.columns = columns.iter().map(|c| c.to_string()).collect();
+    self
+  }
+
+  /// Produces the index keys with the extractor registered as `name`
+  /// instead of table columns. The index keys are strings, named by the
+  /// single entry passed to `columns`.
+  pub fn extractor(mut self, name: &str) -> IndexOptions {
+    self.extractor = Some(name.to_string());
+    self
+  }
+
+  /// Application data stored with the index, also passed to the extractor
+  /// when it is customized for the index.
+  pub fn app_metadata(mut self, app_metadata: &str) -> IndexOptions {
+    self.app_metadata = Some(app_metadata.to_string());
+    self
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = Vec::new();
+
+    if !self.columns.is_empty() {
+      config.push(format!("columns=({0})", self.columns.join(",")));
+    }
+    if let Some(ref extractor) = self.extractor {
+      config.push(format!("extractor={0},key_format=S", extractor));
+    }
+    if let Some(ref app_metadata) = self.app_metadata {
+      config.push(format!("app_metadata=\"{0}\"", app_metadata));
+    }
+
+    config.join(",")
+  }
+}
+
+impl StatisticsLog {
+  /// Returns the options writing the connection statistics every `wait`,
+  /// which is rounded up to whole seconds, and to at least one second since
+  /// Wired Tiger writes no statistics with a wait of zero.
+  pub fn new(wait: Duration) -> StatisticsLog {
+    StatisticsLog{
+      wait: wait,
+      path: None,
+      sources: Vec::new(),
+      timestamp: "%b %d %H:%M:%S".to_string(),
+      on_close: false
+    }
+  }
+
+  /// The files written, relative to the database home, expanded with
+  /// `strftime`. Defaults to `WiredTigerStat.%d.%H`.
+  pub fn path(mut self, path: &str) -> StatisticsLog {
+    self.path = Some(path.to_string());
+    self
+  }
+
+  /// Also writes the statistics of the objects `sources`, such as
+  /// `table:users`, or of every object with a URI ending in `:` such as
+  /// `file:`.
+  pub fn sources(mut self, sources: &[&str]) -> StatisticsLog {
+    self.sources = sources.iter().map(|s| s.to_string()).collect();
+    self
+  }
+
+  /// The `strftime` format of the timestamp starting each line. Defaults to
+  /// `%b %d %H:%M:%S`.
+  pub fn timestamp(mut self, format: &str) -> StatisticsLog {
+    self.timestamp = format.to_string();
+    self
+  }
+
+  /// Also writes the statistics when the connection is closed.
+  pub fn on_close(mut self, on_close: bool) -> StatisticsLog {
+    self.on_close = on_close;
+    self
+  }
+
+  /// The `strftime` format of the timestamps in the log files.
+  pub(crate) fn timestamp_format(&self) -> &str {
+    &self.timestamp
+  }
+
+  fn wait_secs(&self) -> u64 {
+    let secs = self.wait.as_secs();
+    if self.wait.subsec_nanos() > 0 || secs == 0 { secs + 1 } else { secs }
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = vec![
+      format!("wait={0}", self.wait_secs()),
+      format!("timestamp=\"{0}\"", self.timestamp)
+    ];
+
+    if let Some(ref path) = self.path {
+      config.push(format!("path=\"{0}\"", path));
+    }
+    if !self.sources.is_empty() {
+      let sources: Vec<String> = self.sources.iter()
+        .map(|s| format!("\"{0}\"", s)).collect();
+      config.push(format!("sources=({0})", sources.join(",")));
+    }
+    if self.on_close {
+      config.push("on_close=true".to_string());
+    }
+
+    format!("statistics_log=({0})", config.join(","))
+  }
+}
+
+impl Logging {
+  /// Returns the options logging every change, removing the log files once
+  /// they are no longer needed for recovery.
+  pub fn new() -> Logging {
+    Logging{
+      archive: true,
+      path: None
+    }
+  }
+
+  /// When `false` log files are kept until they are removed by an
+  /// incremental backup, so every change can still be read from the log.
+  pub fn archive(mut self, archive: bool) -> Logging {
+    self.archive = archive;
+    self
+  }
+
+  /// The directory holding the log files, relative to the database home.
+  pub fn path(mut self, path: &str) -> Logging {
+    self.path = Some(path.to_string());
+    self
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = vec![
+      "enabled=true".to_string(),
+      format!("archive={0}", self.archive)
+    ];
+
+    if let Some(ref path) = self.path {
+      config.push(format!("path=\"{0}\"", path));
+    }
+
+    format!("log=({0})", config.join(","))
+  }
+}
+
+impl ConnectionOptions {
+  /// Returns the default options, which create the database in the current
+  /// directory if it does not exist.
+  pub fn new() -> ConnectionOptions {
+    ConnectionOptions{
+      home: None,
+      create: true,
+      async_ops: None,
+      extensions: Vec::new(),
+      statistics: Vec::new(),
+      statistics_log: None,
+      log: None,
+      event_handler: None
+    }
+  }
+
+  /// The directory holding the database.
+  pub fn home(mut self, home: &str) -> ConnectionOptions {
+    self.home = Some(home.to_string());
+    self
+  }
+
+  /// When `false` opening fails if the database does not exist.
+  pub fn create(mut self, create: bool) -> ConnectionOptions {
+    self.create = create;
+    self
+  }
+
+  /// Starts `threads` worker threads running the operations queued with
+  /// `Connection::async_insert` and friends, allowing up to `ops_max`
+  /// operations to be queued at once.
+  pub fn async_ops(mut self, threads: u32, ops_max: u32) -> ConnectionOptions {
+    self.async_ops = Some((threads, ops_max));
+    self
+  }
+
+  /// Loads the shared library at `path` as a Wired Tiger extension, such as
+  /// a compressor, when the connection is opened.
+  /// # Examples
+  /// ```
+  /// let options = ConnectionOptions::new()
+  ///   .extension("/usr/local/lib/libwiredtiger_snappy.so");
+  /// ```
+  pub fn extension(mut self, path: &str) -> ConnectionOptions {
+    self.extensions.push((path.to_string(), None));
+    self
+  }
+
+  /// Loads the shared library at `path` as a Wired Tiger extension when the
+  /// connection is opened, passing `config` to its initialization function.
+  pub fn extension_with_config(mut self, path: &str, config: &str)
+    -> ConnectionOptions {
+    self.extensions.push((path.to_string(), Some(config.to_string())));
+    self
+  }
+
+  /// Gathers the statistics read with `Connection::stats` and
+  /// `Session::table_stats`. `Fast` keeps the cheaper statistics, `All`
+  /// every statistic and `Clear` resets them each time they are read.
+  pub fn statistics(mut self, statistics: &[CursorStatistics])
+    -> ConnectionOptions {
+    self.statistics = statistics.to_vec();
+    self
+  }
+
+  /// Writes the statistics to log files periodically. Statistics must also
+  /// be gathered with `statistics`.
+  pub fn statistics_log(mut self, statistics_log: StatisticsLog)
+    -> ConnectionOptions {
+    self.statistics_log = Some(statistics_log);
+    self
+  }
+
+  /// Writes every change to the database log, which is replayed when the
+  /// database is opened after a crash or restored from a backup.
+  pub fn log(mut self, log: Logging) -> ConnectionOptions {
+    self.log = Some(log);
+    self
+  }
+
+  /// The directory holding the database, if set.
+  pub(crate) fn home_dir(&self) -> Option<&str> {
+    self.home.as_ref().map(|h| h.as_str())
+  }
+
+  /// Returns the options with logging enabled, keeping the logging options
+  /// already set.
+  pub(crate) fn with_log(&self) -> ConnectionOptions {
+    let mut options = self.clone();
+    if options.log.is_none() {
+      options.log = Some(Logging::new());
+    }
+    options
+  }
+
+  /// Sends the errors, messages and progress reports of the connection to
+  /// `handler` instead of printing them to the standard error.
+  pub fn event_handler<H>(mut self, handler: H) -> ConnectionOptions
+    where H: EventHandler + Send + Sync + 'static {
+    self.event_handler = Some(Arc::new(handler));
+    self
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = Vec::new();
+
+    if self.create {
+      config.push("create".to_string());
+    }
+    if let Some((threads, ops_max)) = self.async_ops {
+      config.push(format!("async=(enabled=true,threads={0},ops_max={1})",
+        threads, ops_max));
+    }
+    if !self.statistics.is_empty() {
+      let statistics: Vec<&str> = self.statistics.iter().map(|s| match *s {
+        CursorStatistics::All => "all",
+        CursorStatistics::Fast => "fast",
+        CursorStatistics::Clear => "clear"
+      }).collect();
+      config.push(format!("statistics=({0})", statistics.join(",")));
+    }
+    if let Some(ref statistics_log) = self.statistics_log {
+      config.push(statistics_log.to_config());
+    }
+    if let Some(ref log) = self.log {
+      config.push(log.to_config());
+    }
+    if !self.extensions.is_empty() {
+      let extensions: Vec<String> = self.extensions.iter()
+        .map(|&(ref path, ref extension_config)| match *extension_config {
+          Some(ref extension_config) => format!("\"{0}\"=(config=\"{1}\")",
+            path, extension_config),
+          None => format!("\"{0}\"", path)
+        })
+        .collect();
+      config.push(format!("extensions=[{0}]", extensions.join(",")));
+    }
+
+    config.join(",")
+  }
+}
+
 impl Connection {
+  /// Wraps a connection opened outside this crate, such as the one Wired
+  /// Tiger passes to an extension. Dropping it leaves the connection open.
+  pub(crate) unsafe fn from_raw(wt_con: *mut WT_CONNECTION) -> Connection {
+    Connection{
+      wt_con: wt_con,
+      handle: Rc::new(ConnectionHandle{
+        wt_con: wt_con,
+        owned: false,
+        event_bridge: None
+      })
+    }
+  }
+
+  /// Returns the home directory of the database.
+  pub(crate) fn home(&self) -> Result<String, String> {
+    unsafe {
+      match (*self.wt_con).get_home {
+        Some(get_home) => Ok(string_from_ptr(get_home(self.wt_con))),
+        None => Err("Failed to get get_home".to_string())
+      }
+    }
+  }
+
   /// Opens a Wired Tiger session and returns a `Session`.
   /// # Examples
   /// ```
@@ -105,33 +806,209 @@ impl Connection {
   /// # Failures
   /// The function returns `Err(message)` if the session failed to open.
   pub fn open_session(&mut self) -> Result<Session, String>{
-    unsafe{
-      match (*self.wt_con).open_session {
-        Some(open_session) => {
-          let mut session: *mut WT_SESSION = mem::uninitialized();
-          let ret = open_session(self.wt_con, ptr::null_mut(),
-            ptr::null_mut(), &mut session);
+    open_session(&self.handle, ptr::null_mut())
+  }
+
+  /// Loads the shared library at `path` as a Wired Tiger extension. `config`
+  /// is the Wired Tiger configuration of the load, such as
+  /// `config=(level=6)` to pass `level=6` to the extension's initialization
+  /// function, or `entry=init_lz4` to name that function.
+  /// # Examples
+  /// ```
+  /// connection.load_extension("/usr/local/lib/libwiredtiger_lz4.so", "");
+  /// session.create_table_with_options("users",
+  ///   &TableOptions::new().block_compressor("lz4"));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the extension failed to load or
+  /// to initialize.
+  pub fn load_extension(&mut self, path: &str, config: &str)
+    -> Result<(), String> {
+    unsafe {
+      match (*self.wt_con).load_extension {
+        Some(load_extension) => {
+          let c_path = try!(c_str(path));
+          let c_config = try!(c_str(config));
+          let ret = load_extension(self.wt_con, c_path.as_ptr(),
+            c_config.as_ptr());
 
           if ret != 0 {
             return Err(get_error(ret));
           }
 
-          Ok(Session{
-            wt_session: session
-          })
-        },
-        None => Err("Failed to get open_session".to_string())
+          Ok(())
+        }
+        None => Err("Failed to get load_extension".to_string())
       }
     }
   }
 }
 
+fn open_session(connection: &Rc<ConnectionHandle>,
+  handler: *mut WT_EVENT_HANDLER) -> Result<Session, String>{
+  let wt_con = connection.wt_con;
+  unsafe{
+    match (*wt_con).open_session {
+      Some(open_session) => {
+        let mut session = MaybeUninit::<*mut WT_SESSION>::uninit();
+        let ret = open_session(wt_con, handler,
+          ptr::null_mut(), session.as_mut_ptr());
+
+        if ret != 0 {
+          return Err(get_error(ret));
+        }
+        let session = session.assume_init();
+
+        Ok(Session{
+          wt_session: session,
+          handle: Rc::new(SessionHandle{
+            wt_session: session,
+            connection: connection.clone()
+          })
+        })
+      },
+      None => Err("Failed to get open_session".to_string())
+    }
+  }
+}
+
+impl CursorOptions {
+  /// Returns the default options, equivalent to opening a cursor without
+  /// any configuration.
+  pub fn new() -> CursorOptions {
+    CursorOptions::default()
+  }
+
+  /// Keys and values are read and written as packed bytes, with
+  /// `Cursor::insert_raw` and friends, instead of as strings. Cursors on
+  /// tables whose formats are not `S` must be raw.
+  pub fn raw(mut self, raw: bool) -> CursorOptions {
+    self.raw = raw;
+    self
+  }
+
+  /// The cursor can only be used to read records.
+  pub fn readonly(mut self, readonly: bool) -> CursorOptions {
+    self.readonly = readonly;
+    self
+  }
+
+  /// The cursor reads the table as of the checkpoint `name`.
+  /// Checkpoint cursors are always read-only.
+  pub fn checkpoint(mut self, name: &str) -> CursorOptions {
+    self.checkpoint = Some(name.to_string());
+    self
+  }
+
+  /// Records inserted through the cursor are appended to a record-number
+  /// table and get a newly allocated key. The cursor must also be `raw`.
+  pub fn append(mut self, append: bool) -> CursorOptions {
+    self.append = append;
+    self
+  }
+
+  /// When `false` inserting an existing key fails instead of replacing its
+  /// value.
+  pub fn overwrite(mut self, overwrite: bool) -> CursorOptions {
+    self.overwrite = Some(overwrite);
+    self
+  }
+
+  /// Every call to `next` positions the cursor on a random record.
+  /// Iterating such a cursor never ends, so bound it with `take`.
+  pub fn next_random(mut self, next_random: bool) -> CursorOptions {
+    self.next_random = next_random;
+    self
+  }
+
+  /// The statistics gathered for a `statistics:` cursor.
+  pub fn statistics(mut self, statistics: &[CursorStatistics]) -> CursorOptions {
+    self.statistics = statistics.to_vec();
+    self
+  }
+
+  /// Keys and values are returned as strings in the given `format`.
+  pub fn dump(mut self, format: DumpFormat) -> CursorOptions {
+    self.dump = Some(format);
+    self
+  }
+
+  /// The objects listed by a `backup:` cursor, such as `log:` to only list
+  /// the log files for an incremental backup.
+  pub fn target(mut self, targets: &[&str]) -> CursorOptions {
+    self.target = targets.iter().map(|t| t.to_string()).collect();
+    self
+  }
+
+  fn is_readonly(&self) -> bool {
+    self.readonly || self.checkpoint.is_some() || !self.statistics.is_empty()
+  }
+
+  fn to_config(&self) -> String {
+    let mut config = Vec::new();
+
+    if self.raw {
+      config.push("raw=true".to_string());
+    }
+    if self.readonly {
+      config.push("readonly=true".to_string());
+    }
+    if let Some(ref name) = self.checkpoint {
+      config.push(format!("checkpoint={0}", name));
+    }
+    if self.append {
+      config.push("append=true".to_string());
+    }
+    if let Some(overwrite) = self.overwrite {
+      config.push(format!("overwrite={0}", overwrite));
+    }
+    if self.next_random {
+      config.push("next_random=true".to_string());
+    }
+    if !self.statistics.is_empty() {
+      let statistics: Vec<&str> = self.statistics.iter().map(|s| match *s {
+        CursorStatistics::All => "\"all\"",
+        CursorStatistics::Fast => "\"fast\"",
+        CursorStatistics::Clear => "\"clear\""
+      }).collect();
+      config.push(format!("statistics=({0})", statistics.join(",")));
+    }
+    if let Some(format) = self.dump {
+      config.push(match format {
+        DumpFormat::Hex => "dump=hex",
+        DumpFormat::Json => "dump=json",
+        DumpFormat::Print => "dump=print"
+      }.to_string());
+    }
+
+    if !self.target.is_empty() {
+      let targets: Vec<String> = self.target.iter()
+        .map(|t| format!("\"{0}\"", t)).collect();
+      config.push(format!("target=({0})", targets.join(",")));
+    }
+
+    config.join(",")
+  }
+}
+
 impl Cursor {
-  fn set_key(&mut self, key: &CString) -> Result<(), String>{
+  /// Sets the key of the next operation. Wired Tiger keeps reading `key`
+  /// after the call, until the cursor is positioned elsewhere, so the cursor
+  /// holds on to it. Unless the cursor is raw, `key` ends with a nul byte.
+  fn set_key(&mut self, key: Vec<u8>) -> Result<(), String>{
+    if !self.options.raw {
+      try!(self.check_string_format("key"));
+    }
+    self.key = key;
     unsafe {
       match(*self.wt_cursor).set_key {
         Some(set_key) => {
-          set_key(self.wt_cursor, key.as_ptr());
+          if self.options.raw {
+            let item = item_from_bytes(&self.key);
+            set_key(self.wt_cursor, &item);
+          } else {
+            set_key(self.wt_cursor, self.key.as_ptr() as *const c_char);
+          }
           Ok(())
         }
         None => Err("Failed to get set_key".to_string())
@@ -139,11 +1016,22 @@ impl Cursor {
     }
   }
 
-  fn set_value(&mut self, value: &CString) -> Result<(), String>{
+  /// Sets the value of the next operation, which the cursor holds on to
+  /// like the key.
+  fn set_value(&mut self, value: Vec<u8>) -> Result<(), String>{
+    if !self.options.raw {
+      try!(self.check_string_format("value"));
+    }
+    self.value = value;
     unsafe {
       match(*self.wt_cursor).set_value {
         Some(set_value) => {
-          set_value(self.wt_cursor, value.as_ptr());
+          if self.options.raw {
+            let item = item_from_bytes(&self.value);
+            set_value(self.wt_cursor, &item);
+          } else {
+            set_value(self.wt_cursor, self.value.as_ptr() as *const c_char);
+          }
           Ok(())
         }
         None => Err("Failed to get set_value".to_string())
@@ -163,9 +1051,116 @@ impl Cursor {
 
           Ok(())
         }
-        None => Err("Failed to get insert".to_string())
+        None => Err("Failed to get insert".to_string())
+      }
+    }
+  }
+
+  fn remove(&mut self) -> Result<(), String>{
+    unsafe {
+      match(*self.wt_cursor).remove {
+        Some(remove) => {
+          let ret = remove(self.wt_cursor);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get remove".to_string())
+      }
+    }
+  }
+
+  /// Inserts the packed `key` and `value` through a cursor opened with the
+  /// `raw` option, whatever the formats of the table.
+  /// # Examples
+  /// ```
+  /// let mut cursor = session.open_cursor_with_options("events",
+  ///   &CursorOptions::new().raw(true)).unwrap();
+  /// cursor.insert_raw(&[0x81], b"started\0");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor is not raw or is
+  /// read-only, or if the pair failed to be inserted.
+  pub fn insert_raw(&mut self, key: &[u8], value: &[u8])
+    -> Result<(), String>{
+    try!(self.check_raw());
+    if self.options.is_readonly() {
+      return Err("Cursor is read-only".to_string());
+    }
+    try!(self.set_key(key.to_vec()));
+    try!(self.set_value(value.to_vec()));
+    self.insert()
+  }
+
+  /// Removes the packed `key` through a cursor opened with the `raw` option.
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor is not raw or is
+  /// read-only, or if the key failed to be removed.
+  pub fn remove_raw(&mut self, key: &[u8]) -> Result<(), String>{
+    try!(self.check_raw());
+    if self.options.is_readonly() {
+      return Err("Cursor is read-only".to_string());
+    }
+    try!(self.set_key(key.to_vec()));
+    self.remove()
+  }
+
+  /// Positions a cursor opened with the `raw` option on the packed `key` and
+  /// returns the packed value.
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor is not raw or the
+  /// key was not found.
+  pub fn search_raw(&mut self, key: &[u8]) -> Result<Vec<u8>, String>{
+    try!(self.check_raw());
+    try!(self.set_key(key.to_vec()));
+    unsafe {
+      match(*self.wt_cursor).search {
+        Some(search) => {
+          let ret = search(self.wt_cursor);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+        }
+        None => return Err("Failed to get search".to_string())
+      }
+    }
+    self.get_raw_value()
+  }
+
+  /// Moves a cursor opened with the `raw` option to the next record and
+  /// returns its packed key and value, or `None` once there are no more
+  /// records.
+  /// # Examples
+  /// ```
+  /// while let Some((key, value)) = cursor.next_raw().unwrap() {
+  ///   // unpack key and value
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor is not raw or the
+  /// record could not be read.
+  pub fn next_raw(&mut self) -> Result<Option<(Vec<u8>, Vec<u8>)>, String>{
+    try!(self.check_raw());
+    unsafe {
+      match(*self.wt_cursor).next {
+        Some(next) => {
+          let ret = next(self.wt_cursor);
+
+          if ret == WT_NOTFOUND {
+            return Ok(None);
+          }
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+        }
+        None => return Err("Failed to get next".to_string())
       }
     }
+    Ok(Some((try!(self.get_raw_key()), try!(self.get_raw_value()))))
   }
 
   /// Inserts the `value` for the given `key` in the table related to the `Cursor`.
@@ -178,11 +1173,13 @@ impl Cursor {
   /// * The `key` fails to be set for the cursor
   /// * The `value` fails to be set for the cursor
   /// * The pair fail to be inserted
+  /// * The cursor was opened as read-only, on a checkpoint or for statistics
   pub fn insert_pair(&mut self, key: &str, value: &str) -> Result<(), String>{
-    let k = c_str(key);
-    let v = c_str(value);
-    try!(self.set_key(&k));
-    try!(self.set_value(&v));
+    if self.options.is_readonly() {
+      return Err("Cursor is read-only".to_string());
+    }
+    try!(self.set_key(try!(c_str(key)).into_bytes_with_nul()));
+    try!(self.set_value(try!(c_str(value)).into_bytes_with_nul()));
     try!(self.insert());
     Ok(())
   }
@@ -211,6 +1208,85 @@ impl Cursor {
     }
   }
 
+  /// Positions the cursor on `key` and returns its value.
+  /// # Examples
+  /// ```
+  /// let name = cursor.search("1");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the key could not be set or
+  /// was not found.
+  pub fn search(&mut self, key: &str) -> Result<String, String>{
+    try!(self.set_key(try!(c_str(key)).into_bytes_with_nul()));
+    unsafe {
+      match(*self.wt_cursor).search {
+        Some(search) => {
+          let ret = search(self.wt_cursor);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+        }
+        None => return Err("Failed to get search".to_string())
+      }
+    }
+    self.get_value()
+  }
+
+  /// Positions the cursor on `key` or, when it does not exist, on the
+  /// closest key in the table. Returns how the key the cursor is positioned
+  /// on compares to `key`.
+  /// # Examples
+  /// ```
+  /// match cursor.search_near("30") {
+  ///   Ok(Ordering::Less) => { /* positioned before "30" */ }
+  ///   Ok(_) => { /* positioned on or after "30" */ }
+  ///   Err(message) => { /* table is empty or search failed */ }
+  /// }
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the key could not be set or
+  /// the table is empty.
+  pub fn search_near(&mut self, key: &str) -> Result<Ordering, String>{
+    try!(self.set_key(try!(c_str(key)).into_bytes_with_nul()));
+    unsafe {
+      match(*self.wt_cursor).search_near {
+        Some(search_near) => {
+          let mut exact: c_int = 0;
+          let ret = search_near(self.wt_cursor, &mut exact);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(exact.cmp(&0))
+        }
+        None => Err("Failed to get search_near".to_string())
+      }
+    }
+  }
+
+  /// Moves the cursor to the next record and returns its key, or `None`
+  /// once there are no more records.
+  pub(crate) fn next_key(&mut self) -> Result<Option<String>, String>{
+    unsafe {
+      match(*self.wt_cursor).next {
+        Some(next) => {
+          let ret = next(self.wt_cursor);
+
+          if ret == WT_NOTFOUND {
+            return Ok(None);
+          }
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+        }
+        None => return Err("Failed to get next".to_string())
+      }
+    }
+    self.get_key().map(Some)
+  }
+
   fn next(&mut self) -> Result<(), String>{
     unsafe {
       match(*self.wt_cursor).next {
@@ -228,12 +1304,18 @@ impl Cursor {
     }
   }
 
+  /// Returns the key of the current record. The key of a raw cursor is
+  /// its packed form, so it is only meaningful for string keys.
   fn get_key(&mut self) -> Result<String, String>{
+    if self.options.raw {
+      return self.get_raw_key().map(|key| string_from_bytes(&key));
+    }
+    try!(self.check_string_format("key"));
     unsafe {
       match(*self.wt_cursor).get_key {
         Some(get_key) => {
-          let key: *mut c_char = mem::uninitialized();
-          let ret = get_key(self.wt_cursor, &key);
+          let mut key: *const c_char = ptr::null();
+          let ret = get_key(self.wt_cursor, &mut key);
 
           if ret != 0 {
             return Err(get_error(ret));
@@ -246,12 +1328,17 @@ impl Cursor {
     }
   }
 
+  /// Returns the value of the current record, read like `get_key`.
   fn get_value(&mut self) -> Result<String, String>{
+    if self.options.raw {
+      return self.get_raw_value().map(|value| string_from_bytes(&value));
+    }
+    try!(self.check_string_format("value"));
     unsafe {
       match(*self.wt_cursor).get_value {
         Some(get_value) => {
-          let value: *mut c_char = mem::uninitialized();
-          let ret = get_value(self.wt_cursor, &value);
+          let mut value: *const c_char = ptr::null();
+          let ret = get_value(self.wt_cursor, &mut value);
 
           if ret != 0 {
             return Err(get_error(ret));
@@ -263,9 +1350,238 @@ impl Cursor {
       }
     }
   }
+
+  fn get_raw_key(&mut self) -> Result<Vec<u8>, String>{
+    unsafe {
+      match(*self.wt_cursor).get_key {
+        Some(get_key) => {
+          let mut item = WT_ITEM::default();
+          let ret = get_key(self.wt_cursor, &mut item);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(slice_from_item(&item).to_vec())
+        }
+        None => Err("Failed to get get_key".to_string())
+      }
+    }
+  }
+
+  fn get_raw_value(&mut self) -> Result<Vec<u8>, String>{
+    unsafe {
+      match(*self.wt_cursor).get_value {
+        Some(get_value) => {
+          let mut item = WT_ITEM::default();
+          let ret = get_value(self.wt_cursor, &mut item);
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(slice_from_item(&item).to_vec())
+        }
+        None => Err("Failed to get get_value".to_string())
+      }
+    }
+  }
+
+  fn check_raw(&self) -> Result<(), String>{
+    if !self.options.raw {
+      return Err("Cursor was not opened with the raw option".to_string());
+    }
+    Ok(())
+  }
+
+  /// Makes sure the `part` of the records, `key` or `value`, is a single
+  /// string, the only format read and written without the `raw` option.
+  fn check_string_format(&self, part: &str) -> Result<(), String>{
+    let format = unsafe {
+      if part == "key" {
+        string_from_ptr((*self.wt_cursor).key_format)
+      } else {
+        string_from_ptr((*self.wt_cursor).value_format)
+      }
+    };
+    if format != "S" {
+      return Err(format!("Cursor {0} format is {1:?}, open the cursor with \
+        the raw option to use it", part, format));
+    }
+    Ok(())
+  }
 }
 
 impl Session {
+  /// Opens another session on the same connection whose events are sent to
+  /// `handler`, which must outlive the returned `Session`.
+  pub(crate) fn open_sibling(&self, handler: *mut WT_EVENT_HANDLER)
+    -> Result<Session, String> {
+    open_session(&self.handle.connection, handler)
+  }
+
+  /// Creates a checkpoint named `name` of every table in the database.
+  /// An existing checkpoint with the same name is replaced.
+  /// # Examples
+  /// ```
+  /// session.checkpoint("nightly");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the checkpoint failed to be
+  /// created.
+  pub fn checkpoint(&mut self, name: &str) -> Result<(), String> {
+    self.run_checkpoint(&format!("name={0}", name))
+  }
+
+  /// Drops the checkpoint named `name` from every table in the database.
+  /// # Examples
+  /// ```
+  /// session.drop_checkpoint("nightly");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the checkpoint failed to be
+  /// dropped.
+  pub fn drop_checkpoint(&mut self, name: &str) -> Result<(), String> {
+    self.run_checkpoint(&format!("drop=({0})", name))
+  }
+
+  /// Returns the names of the checkpoints available for the table
+  /// `table_name`. Checkpoints created internally by Wired Tiger are not
+  /// listed.
+  /// # Examples
+  /// ```
+  /// let checkpoints = session.list_checkpoints("users");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table does not exist or its
+  /// metadata could not be read.
+  pub fn list_checkpoints(&mut self, table_name: &str)
+    -> Result<Vec<String>, String> {
+    let source = try!(self.colgroup_source(table_name));
+    let mut metadata = try!(self.open_uri_cursor("metadata:",
+      &CursorOptions::new()));
+    let config = try!(metadata.search(&source));
+    let mut names = Vec::new();
+
+    for (key, value) in try!(parse_config(&config)) {
+      if key != "checkpoint" {
+        continue;
+      }
+      for (name, _) in try!(parse_config(&value)) {
+        if !name.starts_with("WiredTigerCheckpoint") {
+          names.push(name);
+        }
+      }
+    }
+
+    Ok(names)
+  }
+
+  /// Returns the URI of the object storing the first column group of the
+  /// table `table_name`, such as `file:users.wt`. Every object of a table
+  /// holds the same checkpoints.
+  fn colgroup_source(&mut self, table_name: &str) -> Result<String, String> {
+    let colgroup = format!("colgroup:{0}", table_name);
+    let prefix = format!("{0}:", colgroup);
+    let mut metadata = try!(self.open_uri_cursor("metadata:",
+      &CursorOptions::new()));
+    let config = match metadata
+      .find(|kvp| kvp.key == colgroup || kvp.key.starts_with(&prefix)) {
+      Some(kvp) => kvp.value,
+      None => return Err(format!("No column group found for {0}", table_name))
+    };
+
+    for (key, value) in try!(parse_config(&config)) {
+      if key == "source" {
+        return Ok(value);
+      }
+    }
+    Err(format!("No source found for {0}", colgroup))
+  }
+
+  /// Opens a read-only `Cursor` that reads the table `table_name` as of the
+  /// checkpoint `name`.
+  /// # Examples
+  /// ```
+  /// session.open_cursor_at_checkpoint("users", "nightly");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor could not be opened.
+  pub fn open_cursor_at_checkpoint(&mut self, table_name: &str, name: &str)
+    -> Result<Cursor, String>{
+    self.open_cursor_with_options(table_name,
+      &CursorOptions::new().checkpoint(name))
+  }
+
+  /// Removes the log files listed by the `backup:` cursor `backup` once
+  /// they have been copied.
+  pub(crate) fn archive_logs(&mut self, backup: &mut Cursor)
+    -> Result<(), String> {
+    let uri = try!(c_str("log:"));
+    self.run_truncate(uri.as_ptr(), backup.wt_cursor, ptr::null_mut())
+  }
+
+  /// Starts a transaction on the session.
+  pub(crate) fn begin_transaction(&mut self) -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).begin_transaction };
+    self.call_with_config(method, "begin_transaction", "")
+  }
+
+  /// Commits the transaction running on the session.
+  pub(crate) fn commit_transaction(&mut self) -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).commit_transaction };
+    self.call_with_config(method, "commit_transaction", "")
+  }
+
+  /// Rolls back the transaction running on the session.
+  pub(crate) fn rollback_transaction(&mut self) -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).rollback_transaction };
+    self.call_with_config(method, "rollback_transaction", "")
+  }
+
+  fn run_checkpoint(&mut self, config: &str) -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).checkpoint };
+    self.call_with_config(method, "checkpoint", config)
+  }
+
+  pub(crate) fn call_with_uri(&mut self,
+    method: Option<extern "C" fn(*mut WT_SESSION, *const c_char,
+      *const c_char) -> c_int>,
+    method_name: &str, uri: &str, config: &str) -> Result<(), String> {
+    match method {
+      Some(method) => {
+        let c_uri = try!(c_str(uri));
+        let c_config = try!(c_str(config));
+        let ret = method(self.wt_session, c_uri.as_ptr(), c_config.as_ptr());
+
+        if ret != 0 {
+          return Err(unsafe { get_error(ret) });
+        }
+
+        Ok(())
+      }
+      None => Err(format!("Failed to get {0}", method_name))
+    }
+  }
+
+  fn call_with_config(&mut self,
+    method: Option<extern "C" fn(*mut WT_SESSION, *const c_char) -> c_int>,
+    method_name: &str, config: &str) -> Result<(), String> {
+    match method {
+      Some(method) => {
+        let c_config = try!(c_str(config));
+        let ret = method(self.wt_session, c_config.as_ptr());
+
+        if ret != 0 {
+          return Err(unsafe { get_error(ret) });
+        }
+
+        Ok(())
+      }
+      None => Err(format!("Failed to get {0}", method_name))
+    }
+  }
+
   /// Creates a table named `name` to hold key/value pairs.
   /// # Examples
   /// ```
@@ -274,13 +1590,46 @@ impl Session {
   /// # Failures
   /// The function returns `Err(message)` if the table failed to be created.
   pub fn create_table(&mut self, name: &str) -> Result<(), String> {
+    self.create_table_with_options(name, &TableOptions::new())
+  }
+
+  /// Creates a table named `name` configured with `options`.
+  /// # Examples
+  /// ```
+  /// let options = TableOptions::new().collator("case_insensitive");
+  /// session.create_table_with_options("users", &options);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table failed to be created.
+  pub fn create_table_with_options(&mut self, name: &str,
+    options: &TableOptions) -> Result<(), String> {
+    self.create(&format!("table:{0}", name), &options.to_config())
+  }
+
+  /// Creates an index named `name` on the table `table_name`.
+  /// # Examples
+  /// ```
+  /// session.create_index("users", "by_city",
+  ///   &IndexOptions::new().columns(&["city"]));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the index failed to be created.
+  pub fn create_index(&mut self, table_name: &str, name: &str,
+    options: &IndexOptions) -> Result<(), String> {
+    self.create(&format!("index:{0}:{1}", table_name, name),
+      &options.to_config())
+  }
+
+  pub(crate) fn create(&mut self, uri: &str, config: &str)
+    -> Result<(), String> {
     unsafe {
       match(*self.wt_session).create {
         Some(create) => {
-          let full_command = &format!("table:{0}", name);
+          let c_uri = try!(c_str(uri));
+          let c_config = try!(c_str(config));
           let ret = create(self.wt_session,
-            c_str(full_command).as_ptr(),
-            c_str("key_format=S,value_format=S").as_ptr());
+            c_uri.as_ptr(),
+            c_config.as_ptr());
 
           if ret != 0 {
             return Err(get_error(ret));
@@ -293,6 +1642,113 @@ impl Session {
     }
   }
 
+  /// Upgrades the object `uri`, such as `table:users`, to the on-disk format
+  /// of the Wired Tiger library in use.
+  /// The object must not be open in any other session.
+  /// # Examples
+  /// ```
+  /// session.upgrade("table:users");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the object failed to be
+  /// upgraded.
+  pub fn upgrade(&mut self, uri: &str) -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).upgrade };
+    self.call_with_uri(method, "upgrade", uri, "")
+  }
+
+  /// Drops the table `name` and removes its files.
+  /// When `force` is `true` a missing table is not reported as an error.
+  /// # Examples
+  /// ```
+  /// session.drop_table("users", false);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table failed to be dropped,
+  /// for example because a cursor is still open on it.
+  pub fn drop_table(&mut self, name: &str, force: bool) -> Result<(), String> {
+    let method = unsafe { (*self.wt_session).drop };
+    self.call_with_uri(method, "drop", &format!("table:{0}", name),
+      &format!("force={0}", force))
+  }
+
+  /// Renames the table `old_name` to `new_name`.
+  /// # Examples
+  /// ```
+  /// session.rename_table("users", "customers");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table failed to be renamed.
+  pub fn rename_table(&mut self, old_name: &str, new_name: &str)
+    -> Result<(), String> {
+    unsafe {
+      match(*self.wt_session).rename {
+        Some(rename) => {
+          let uri = try!(c_str(&format!("table:{0}", old_name)));
+          let new_uri = try!(c_str(&format!("table:{0}", new_name)));
+          let ret = rename(self.wt_session,
+            uri.as_ptr(),
+            new_uri.as_ptr(),
+            ptr::null());
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get rename".to_string())
+      }
+    }
+  }
+
+  /// Removes every record from the table `name`.
+  /// # Examples
+  /// ```
+  /// session.truncate("users");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the table failed to be truncated.
+  pub fn truncate(&mut self, name: &str) -> Result<(), String> {
+    let uri = try!(c_str(&format!("table:{0}", name)));
+    self.run_truncate(uri.as_ptr(), ptr::null_mut(), ptr::null_mut())
+  }
+
+  /// Removes the records between the positions of the `start` and `stop`
+  /// cursors, both included, in a single operation.
+  /// Both cursors must be open on the same table.
+  /// # Examples
+  /// ```
+  /// try!(start.search_near("2015-01-01"));
+  /// try!(stop.search_near("2015-12-31"));
+  /// session.truncate_range(&start, &stop);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the range failed to be
+  /// truncated.
+  pub fn truncate_range(&mut self, start: &Cursor, stop: &Cursor)
+    -> Result<(), String> {
+    self.run_truncate(ptr::null(), start.wt_cursor, stop.wt_cursor)
+  }
+
+  fn run_truncate(&mut self, uri: *const c_char, start: *mut WT_CURSOR,
+    stop: *mut WT_CURSOR) -> Result<(), String> {
+    unsafe {
+      match(*self.wt_session).truncate {
+        Some(truncate) => {
+          let ret = truncate(self.wt_session, uri, start, stop, ptr::null());
+
+          if ret != 0 {
+            return Err(get_error(ret));
+          }
+
+          Ok(())
+        }
+        None => Err("Failed to get truncate".to_string())
+      }
+    }
+  }
+
   /// Opens a `Cursor` for the table `table_name` and returns it.
   /// # Examples
   /// ```
@@ -301,15 +1757,68 @@ impl Session {
   /// # Failures
   /// The function returns `Err(message)` if the cursor could not be opened.
   pub fn open_cursor(&mut self, table_name: &str) -> Result<Cursor, String>{
+    self.open_cursor_with_options(table_name, &CursorOptions::new())
+  }
+
+  /// Opens a `Cursor` for the table `table_name` configured with `options`
+  /// and returns it.
+  /// # Examples
+  /// ```
+  /// let options = CursorOptions::new().overwrite(false);
+  /// session.open_cursor_with_options("users", &options);
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor could not be opened.
+  pub fn open_cursor_with_options(&mut self, table_name: &str,
+    options: &CursorOptions) -> Result<Cursor, String>{
+    self.open_uri_cursor(&format!("table:{0}", table_name), options)
+  }
+
+  /// Opens a `Cursor` on the index `index_name` of the table `table_name`
+  /// and returns it. Its keys are the index keys and its values the values
+  /// of the table.
+  /// # Examples
+  /// ```
+  /// let mut by_city = session.open_index_cursor("users", "by_city").unwrap();
+  /// let name = by_city.search("Paris");
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor could not be opened.
+  pub fn open_index_cursor(&mut self, table_name: &str, index_name: &str)
+    -> Result<Cursor, String>{
+    self.open_uri_cursor(&format!("index:{0}:{1}", table_name, index_name),
+      &CursorOptions::new())
+  }
+
+  /// Opens a `Cursor` on the object `uri` configured with `options` and
+  /// returns it, for cursors that are not on a table such as `backup:`,
+  /// `statistics:table:users` or `index:users:by_city`.
+  /// # Examples
+  /// ```
+  /// let options = CursorOptions::new().target(&["log:"]);
+  /// let backup = session.open_uri_cursor("backup:", &options);
+  /// let dump = session.open_uri_cursor("table:users",
+  ///   &CursorOptions::new().dump(DumpFormat::Json));
+  /// ```
+  /// # Failures
+  /// The function returns `Err(message)` if the cursor could not be opened,
+  /// or if `options` set `append` without `raw`, as appended records have
+  /// record number keys.
+  pub fn open_uri_cursor(&mut self, uri: &str, options: &CursorOptions)
+    -> Result<Cursor, String>{
+    if options.append && !options.raw {
+      return Err("Appending requires the raw option".to_string());
+    }
     unsafe {
       match(*self.wt_session).open_cursor {
         Some(open_cursor) => {
-          let mut cursor: *mut WT_CURSOR = mem::uninitialized();
-          let cursor_uid = &format!("table:{0}", table_name);
+          let mut cursor: *mut WT_CURSOR = ptr::null_mut();
+          let cursor_uid = try!(c_str(uri));
+          let config = try!(c_str(&options.to_config()));
           let ret = open_cursor(self.wt_session,
-            c_str(cursor_uid).as_ptr(),
-            ptr::null_mut(),
+            cursor_uid.as_ptr(),
             ptr::null_mut(),
+            config.as_ptr(),
             &mut cursor);
 
           if ret != 0 {
@@ -317,7 +1826,11 @@ impl Session {
           }
 
           Ok(Cursor{
-            wt_cursor: cursor
+            wt_cursor: cursor,
+            options: options.clone(),
+            _session: self.handle.clone(),
+            key: Vec::new(),
+            value: Vec::new()
           })
         }
         None => Err("Failed to get open_cursor".to_string())
@@ -345,8 +1858,7 @@ impl Iterator for Cursor {
 }
 
 impl Drop for Cursor {
-  /// When the connection is dropped the underlying connection is closed.
-  /// All other components related to the connection are no longer usable.
+  /// Closes the underlying cursor, then releases its session.
   fn drop(&mut self) {
     unsafe{
       match (*self.wt_cursor).close {
@@ -357,9 +1869,9 @@ impl Drop for Cursor {
   }
 }
 
-impl Drop for Session {
-  /// When the connection is dropped the underlying connection is closed.
-  /// All other components related to the connection are no longer usable.
+impl Drop for SessionHandle {
+  /// Closes the underlying session once nothing uses it anymore, then
+  /// releases its connection.
   fn drop(&mut self) {
     unsafe{
       match (*self.wt_session).close {
@@ -368,4 +1880,121 @@ impl Drop for Session {
       };
     }
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::{ConnectionOptions, CursorOptions, CursorStatistics, DumpFormat,
+    IndexOptions, Logging, StatisticsLog, TableOptions, c_str, item_from_bytes,
+    slice_from_item, strip_brackets};
+  use wiredtiger_def::WT_ITEM;
+  use std::ptr;
+  use std::time::Duration;
+
+  #[test]
+  fn c_str_rejects_nul_bytes() {
+    assert_eq!(c_str("users").unwrap().as_bytes(), b"users");
+    assert!(c_str("us\0ers").is_err());
+  }
+
+  #[test]
+  fn items_round_trip_bytes() {
+    let bytes = [0u8, 1, 2, 255];
+    let item = item_from_bytes(&bytes);
+    assert_eq!(unsafe { slice_from_item(&item) }, &bytes[..]);
+
+    let empty = item_from_bytes(&[]);
+    assert_eq!(unsafe { slice_from_item(&empty) }, &[] as &[u8]);
+    assert_eq!(unsafe { slice_from_item(&WT_ITEM::default()) }, &[] as &[u8]);
+    assert_eq!(unsafe { slice_from_item(ptr::null()) }, &[] as &[u8]);
+  }
+
+  #[test]
+  fn strips_only_balanced_brackets() {
+    assert_eq!(strip_brackets("(a=1,b=2)"), "a=1,b=2");
+    assert_eq!(strip_brackets("[a,b]"), "a,b");
+    assert_eq!(strip_brackets("()"), "");
+    assert_eq!(strip_brackets("("), "(");
+    assert_eq!(strip_brackets("(a=1"), "(a=1");
+    assert_eq!(strip_brackets("(a=1]"), "(a=1]");
+    assert_eq!(strip_brackets("a=1"), "a=1");
+    assert_eq!(strip_brackets(""), "");
+  }
+
+  #[test]
+  fn cursor_options_config() {
+    assert_eq!(CursorOptions::new().to_config(), "");
+    let options = CursorOptions::new()
+      .raw(true)
+      .readonly(true)
+      .checkpoint("nightly")
+      .overwrite(false)
+      .next_random(true)
+      .statistics(&[CursorStatistics::Fast, CursorStatistics::Clear])
+      .dump(DumpFormat::Json)
+      .target(&["log:"]);
+    assert_eq!(options.to_config(), "raw=true,readonly=true,\
+      checkpoint=nightly,overwrite=false,next_random=true,\
+      statistics=(\"fast\",\"clear\"),dump=json,target=(\"log:\")");
+    assert_eq!(CursorOptions::new().append(true).to_config(), "append=true");
+  }
+
+  #[test]
+  fn table_and_index_options_config() {
+    assert_eq!(TableOptions::new().to_config(), "key_format=S,value_format=S");
+    let options = TableOptions::new()
+      .key_format("r")
+      .value_format("SS")
+      .columns(&["id", "name", "city"])
+      .collator("reverse")
+      .block_compressor("lz4")
+      .data_source("memory")
+      .app_metadata("version=2");
+    assert_eq!(options.to_config(), "key_format=r,value_format=SS,\
+      columns=(id,name,city),collator=reverse,block_compressor=lz4,\
+      type=memory,app_metadata=\"version=2\"");
+
+    assert_eq!(IndexOptions::new().columns(&["city"]).to_config(),
+      "columns=(city)");
+    assert_eq!(IndexOptions::new().columns(&["tag"]).extractor("tags")
+      .to_config(), "columns=(tag),extractor=tags,key_format=S");
+  }
+
+  #[test]
+  fn connection_options_config() {
+    assert_eq!(ConnectionOptions::new().to_config(), "create");
+    let options = ConnectionOptions::new()
+      .create(false)
+      .async_ops(2, 1024)
+      .statistics(&[CursorStatistics::All])
+      .statistics_log(StatisticsLog::new(Duration::from_secs(30))
+        .sources(&["table:users"])
+        .on_close(true))
+      .log(Logging::new().archive(false).path("journal"))
+      .extension("/usr/lib/libwiredtiger_lz4.so")
+      .extension_with_config("/usr/lib/libnumeric.so", "digits=10");
+    assert_eq!(options.to_config(), "async=(enabled=true,threads=2,\
+      ops_max=1024),statistics=(all),statistics_log=(wait=30,\
+      timestamp=\"%b %d %H:%M:%S\",sources=(\"table:users\"),\
+      on_close=true),log=(enabled=true,archive=false,path=\"journal\"),\
+      extensions=[\"/usr/lib/libwiredtiger_lz4.so\",\
+      \"/usr/lib/libnumeric.so\"=(config=\"digits=10\")]");
+  }
+
+  #[test]
+  fn statistics_log_wait_is_rounded_up_to_seconds() {
+    let wait = |wait| StatisticsLog::new(wait).wait_secs();
+    assert_eq!(wait(Duration::from_secs(30)), 30);
+    assert_eq!(wait(Duration::from_millis(1500)), 2);
+    assert_eq!(wait(Duration::from_millis(10)), 1);
+    assert_eq!(wait(Duration::from_secs(0)), 1);
+  }
+
+  #[test]
+  fn with_log_keeps_the_logging_options_set() {
+    assert_eq!(ConnectionOptions::new().with_log().to_config(),
+      "create,log=(enabled=true,archive=true)");
+    let options = ConnectionOptions::new().log(Logging::new().archive(false));
+    assert_eq!(options.with_log().to_config(),
+      "create,log=(enabled=true,archive=false)");
+  }
+}
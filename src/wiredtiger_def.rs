@@ -393,7 +393,90 @@ pub type WT_CONNECTION = Struct___wt_connection;
 pub type WT_CURSOR = Struct___wt_cursor;
 pub type WT_DATA_SOURCE = Struct___wt_data_source;
 pub type WT_EVENT_HANDLER = Struct___wt_event_handler;
-pub enum Struct___wt_extension_api { }
+/* Declared by hand from wiredtiger_ext.h, which is not part of the generated
+ * bindings. The structure is append-only, so only the leading fields used
+ * through a pointer are declared. */
+#[repr(C)]
+#[derive(Copy)]
+pub struct Struct___wt_extension_api {
+    pub conn: *mut WT_CONNECTION,
+    pub err_printf: ::std::option::Option<extern "C" fn(wt_api:
+                                                            *mut WT_EXTENSION_API,
+                                                        session:
+                                                            *mut WT_SESSION,
+                                                        fmt:
+                                                            *const libc::c_char, ...)
+                                              -> libc::c_int>,
+    pub msg_printf: ::std::option::Option<extern "C" fn(wt_api:
+                                                            *mut WT_EXTENSION_API,
+                                                        session:
+                                                            *mut WT_SESSION,
+                                                        fmt:
+                                                            *const libc::c_char, ...)
+                                              -> libc::c_int>,
+    pub strerror: ::std::option::Option<extern "C" fn(wt_api:
+                                                          *mut WT_EXTENSION_API,
+                                                      session: *mut WT_SESSION,
+                                                      error: libc::c_int)
+                                            -> *const libc::c_char>,
+    pub scr_alloc: ::std::option::Option<extern "C" fn(wt_api:
+                                                           *mut WT_EXTENSION_API,
+                                                       session:
+                                                           *mut WT_SESSION,
+                                                       bytes: size_t)
+                                             -> *mut libc::c_void>,
+    pub scr_free: ::std::option::Option<extern "C" fn(wt_api:
+                                                          *mut WT_EXTENSION_API,
+                                                      session: *mut WT_SESSION,
+                                                      _ref: *mut libc::c_void)
+                                            -> ()>,
+    pub collator_config: ::std::option::Option<extern "C" fn(wt_api:
+                                                                 *mut WT_EXTENSION_API,
+                                                             session:
+                                                                 *mut WT_SESSION,
+                                                             uri:
+                                                                 *const libc::c_char,
+                                                             config:
+                                                                 *mut WT_CONFIG_ARG,
+                                                             collatorp:
+                                                                 *mut *mut WT_COLLATOR,
+                                                             ownp:
+                                                                 *mut libc::c_int)
+                                                   -> libc::c_int>,
+    pub collate: ::std::option::Option<extern "C" fn(wt_api:
+                                                         *mut WT_EXTENSION_API,
+                                                     session: *mut WT_SESSION,
+                                                     collator:
+                                                         *mut WT_COLLATOR,
+                                                     first: *mut WT_ITEM,
+                                                     second: *mut WT_ITEM,
+                                                     cmp: *mut libc::c_int)
+                                           -> libc::c_int>,
+    pub config_get: ::std::option::Option<extern "C" fn(wt_api:
+                                                            *mut WT_EXTENSION_API,
+                                                        session:
+                                                            *mut WT_SESSION,
+                                                        config:
+                                                            *mut WT_CONFIG_ARG,
+                                                        key:
+                                                            *const libc::c_char,
+                                                        value:
+                                                            *mut WT_CONFIG_ITEM)
+                                              -> libc::c_int>,
+    pub config_parser_open: ::std::option::Option<extern "C" fn(wt_api:
+                                                                    *mut WT_EXTENSION_API,
+                                                                session:
+                                                                    *mut WT_SESSION,
+                                                                config:
+                                                                    *const libc::c_char,
+                                                                len: size_t,
+                                                                config_parserp:
+                                                                    *mut *mut WT_CONFIG_PARSER)
+                                                      -> libc::c_int>,
+}
+impl ::std::clone::Clone for Struct___wt_extension_api {
+    fn clone(&self) -> Self { *self }
+}
 pub type WT_EXTENSION_API = Struct___wt_extension_api;
 pub type WT_EXTRACTOR = Struct___wt_extractor;
 pub type WT_ITEM = Struct___wt_item;
@@ -0,0 +1,88 @@
+extern crate wiredtiger;
+
+use wiredtiger::wiredtiger::{open_with_options, ConnectionOptions,
+  CursorOptions, TableOptions};
+use wiredtiger::data_source::MemoryDataSource;
+
+use std::cmp::Ordering;
+use std::env;
+use std::fs;
+use std::process;
+
+fn empty_home(name: &str) -> String {
+  let home = env::temp_dir()
+    .join(format!("wiredtiger-{0}-{1}", name, process::id()));
+  let _ = fs::remove_dir_all(&home);
+  fs::create_dir_all(&home).unwrap();
+  home.to_string_lossy().to_string()
+}
+
+#[test]
+fn tables_stored_in_a_memory_data_source() {
+  let home = empty_home("data-source");
+  let mut connection = open_with_options(&ConnectionOptions::new()
+    .home(&home)).unwrap();
+  connection.add_data_source("memory:", MemoryDataSource::new()).unwrap();
+  let mut session = connection.open_session().unwrap();
+  session.create_table_with_options("users",
+    &TableOptions::new().data_source("memory")).unwrap();
+
+  {
+    let mut cursor = session.open_cursor("users").unwrap();
+    cursor.insert_pair("bob", "2").unwrap();
+    cursor.insert_pair("alice", "1").unwrap();
+    cursor.insert_pair("dave", "4").unwrap();
+    cursor.insert_pair("alice", "3").unwrap();
+
+    assert_eq!(cursor.search("alice"), Ok("3".to_string()));
+    assert!(cursor.search("carol").is_err());
+    assert_eq!(cursor.search_near("carol"), Ok(Ordering::Greater));
+    assert_eq!(cursor.search_near("bob"), Ok(Ordering::Equal));
+
+    cursor.reset().unwrap();
+    let records: Vec<(String, String)> = cursor.by_ref()
+      .map(|kvp| (kvp.key, kvp.value))
+      .collect();
+    assert_eq!(records, vec![
+      ("alice".to_string(), "3".to_string()),
+      ("bob".to_string(), "2".to_string()),
+      ("dave".to_string(), "4".to_string())
+    ]);
+  }
+
+  {
+    let options = CursorOptions::new().overwrite(false);
+    let mut cursor = session.open_cursor_with_options("users", &options)
+      .unwrap();
+    assert!(cursor.insert_pair("alice", "5").is_err());
+    assert_eq!(cursor.search("alice"), Ok("3".to_string()));
+    cursor.insert_pair("erin", "5").unwrap();
+  }
+
+  {
+    let mut cursor = session.open_cursor_with_options("users",
+      &CursorOptions::new().raw(true)).unwrap();
+    cursor.remove_raw(b"bob\0").unwrap();
+    assert!(cursor.remove_raw(b"bob\0").is_err());
+    let keys: Vec<String> = session.open_cursor("users").unwrap()
+      .map(|kvp| kvp.key)
+      .collect();
+    assert_eq!(keys, vec!["alice", "dave", "erin"]);
+  }
+
+  session.truncate("users").unwrap();
+  assert_eq!(session.open_cursor("users").unwrap().count(), 0);
+
+  session.open_cursor("users").unwrap().insert_pair("frank", "6").unwrap();
+  session.rename_table("users", "customers").unwrap();
+  assert!(session.open_cursor("users").is_err());
+  assert_eq!(session.open_cursor("customers").unwrap().search("frank"),
+    Ok("6".to_string()));
+
+  session.drop_table("customers", false).unwrap();
+  assert!(session.open_cursor("customers").is_err());
+
+  drop(session);
+  drop(connection);
+  fs::remove_dir_all(&home).unwrap();
+}